@@ -0,0 +1,35 @@
+//! Times collecting the first `10^6` primes (or the first argument) with the
+//! unbounded `HeapSieve` and `LazyPrimeIter`, and with `first_n_primes`.
+//!
+//! Run with `cargo run --release --example bench_heap_sieve [count]`.
+
+use rusty_primes::{
+    counting::first_n_primes,
+    iter::{HeapSieve, LazyPrimeIter},
+};
+use std::{env, hint::black_box, time::Instant};
+
+fn main() {
+    let count = env::args()
+        .nth(1)
+        .map_or(1_000_000, |s| s.parse().expect("invalid count"));
+
+    let start = Instant::now();
+    let heap: Vec<usize> = HeapSieve::new().take(black_box(count)).collect();
+    let heap_time = start.elapsed();
+
+    let start = Instant::now();
+    let lazy: Vec<usize> = LazyPrimeIter::new().take(black_box(count)).collect();
+    let lazy_time = start.elapsed();
+
+    let start = Instant::now();
+    let segmented = first_n_primes(black_box(count));
+    let segmented_time = start.elapsed();
+
+    assert_eq!(heap, segmented);
+    assert_eq!(lazy, segmented);
+    println!("the first {} primes, up to {:?}", count, segmented.last());
+    println!("HeapSieve:      {:.2?}", heap_time);
+    println!("LazyPrimeIter:  {:.2?}", lazy_time);
+    println!("first_n_primes: {:.2?}", segmented_time);
+}
@@ -0,0 +1,26 @@
+//! Times counting the primes up to `10^9` (or the first argument) with
+//! `SegmentedEratosthenes` and `OddSegmentedEratosthenes`.
+//!
+//! Run with `cargo run --release --example bench_odd_segmented [n]`.
+
+use rusty_primes::sieve::{OddSegmentedEratosthenes, SegmentedEratosthenes, Sieve};
+use std::{env, hint::black_box, time::Instant};
+
+fn main() {
+    let n = env::args()
+        .nth(1)
+        .map_or(1_000_000_000, |s| s.parse().expect("invalid n"));
+
+    let start = Instant::now();
+    let segmented = SegmentedEratosthenes::prime_pi(black_box(n));
+    let segmented_time = start.elapsed();
+
+    let start = Instant::now();
+    let odd = OddSegmentedEratosthenes::prime_pi(black_box(n));
+    let odd_time = start.elapsed();
+
+    assert_eq!(segmented, odd);
+    println!("π({}) = {}", n, segmented);
+    println!("SegmentedEratosthenes:    {:.2?}", segmented_time);
+    println!("OddSegmentedEratosthenes: {:.2?}", odd_time);
+}
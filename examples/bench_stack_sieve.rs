@@ -0,0 +1,50 @@
+//! Times repeatedly counting the primes up to 1000 by re-sieving a `StackSieve`
+//! in place, against generating a new table with `gen_table` each time.
+//!
+//! Run with `cargo run --release --example bench_stack_sieve [iterations]`.
+
+use rusty_primes::{
+    sieve::Eratosthenes,
+    stack::{words_for, StackSieve},
+};
+use std::{env, hint::black_box, time::Instant};
+
+const LIMIT: usize = 1000;
+
+fn main() {
+    let iters: u32 = env::args()
+        .nth(1)
+        .map_or(1_000_000, |s| s.parse().expect("invalid iterations"));
+
+    let mut sieve = StackSieve::<{ words_for(LIMIT) }>::new(LIMIT);
+    let start = Instant::now();
+    let mut stack_sum = 0;
+    for _ in 0..iters {
+        sieve.sieve(black_box(LIMIT));
+        stack_sum += sieve.count_ones();
+    }
+    let stack_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut heap_sum = 0;
+    for _ in 0..iters {
+        heap_sum += Eratosthenes::gen_table(black_box(LIMIT)).count_ones();
+    }
+    let heap_time = start.elapsed();
+
+    assert_eq!(stack_sum, heap_sum);
+    println!(
+        "π({}) = {}, {} iterations",
+        LIMIT,
+        sieve.count_ones(),
+        iters
+    );
+    println!(
+        "StackSieve::sieve:  {:.0?} per iteration",
+        stack_time / iters
+    );
+    println!(
+        "gen_table:          {:.0?} per iteration",
+        heap_time / iters
+    );
+}
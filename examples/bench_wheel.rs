@@ -0,0 +1,77 @@
+//! Times counting the primes up to `u32::MAX` (or the first argument) with
+//! `SegmentedEratosthenes`, whose segments are pre-marked from a 2·3·5·7 wheel
+//! pattern, against a plain segmented sieve marking every base prime one by one.
+//!
+//! Run with `cargo run --release --example bench_wheel [n]`.
+
+use rusty_primes::{
+    bitset::BitSet,
+    sieve::{Eratosthenes, SegmentedEratosthenes, Sieve},
+};
+use std::{env, hint::black_box, time::Instant};
+
+fn main() {
+    let n = env::args()
+        .nth(1)
+        .map_or(u32::MAX as usize, |s| s.parse().expect("invalid n"));
+
+    let start = Instant::now();
+    let wheel = SegmentedEratosthenes::prime_pi(black_box(n));
+    let wheel_time = start.elapsed();
+
+    let start = Instant::now();
+    let plain = plain_prime_pi(black_box(n));
+    let plain_time = start.elapsed();
+
+    assert_eq!(wheel, plain);
+    println!("π({}) = {}", n, wheel);
+    println!("wheel pre-marked: {:.2?}", wheel_time);
+    println!("plain segmented:  {:.2?}", plain_time);
+}
+
+/// The segmented sieve without the wheel, with segments of length `sqrt(n)`.
+fn plain_prime_pi(n: usize) -> usize {
+    if n < 2 {
+        return 0;
+    }
+    let mut seg_len = (n as f64).sqrt() as usize;
+    while seg_len * seg_len > n {
+        seg_len -= 1;
+    }
+    while (seg_len + 1) * (seg_len + 1) <= n {
+        seg_len += 1;
+    }
+    let primes: Vec<usize> = Eratosthenes::gen_table(seg_len).iter_ones().collect();
+
+    let mut res = primes.len();
+    let mut seg = BitSet::new(seg_len, true);
+    let mut low = seg_len + 1;
+    while low <= n {
+        let high = low.saturating_add(seg_len - 1).min(n);
+        let len = high - low + 1;
+        seg.set_all();
+        for &p in &primes {
+            if p * p > high {
+                break;
+            }
+            let mut i = if low <= p * p {
+                p * p - low
+            } else {
+                (p - low % p) % p
+            };
+            while i < len {
+                unsafe { seg.clear(i) }
+                i += p;
+            }
+        }
+        for i in len..seg_len {
+            unsafe { seg.clear(i) }
+        }
+        res += seg.count_ones();
+        if high == n {
+            break;
+        }
+        low = high + 1;
+    }
+    res
+}
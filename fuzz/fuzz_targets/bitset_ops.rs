@@ -0,0 +1,61 @@
+//! Applies a sequence of operations to a `BitSet` and a `Vec<bool>` oracle,
+//! asserting that they agree.
+//!
+//! The input starts with a little-endian `u16` length, followed by
+//! operations of three bytes each: an opcode and a little-endian `u16`
+//! argument, which is reduced to an index below the current length.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rusty_primes::bitset::BitSet;
+
+/// The maximum length of the bitset, spanning a few words.
+const MAX_LEN: usize = 512;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+    let len = u16::from_le_bytes([data[0], data[1]]) as usize % MAX_LEN + 1;
+    let initial_v = len & 1 == 1;
+    let mut bitset = BitSet::new(len, initial_v);
+    let mut oracle = vec![initial_v; len];
+
+    for op in data[2..].chunks_exact(3) {
+        let arg = u16::from_le_bytes([op[1], op[2]]) as usize;
+        let i = arg % oracle.len();
+        match op[0] % 6 {
+            0 => {
+                unsafe { bitset.set(i) }
+                oracle[i] = true;
+            }
+            1 => {
+                unsafe { bitset.clear(i) }
+                oracle[i] = false;
+            }
+            2 => assert_eq!(unsafe { bitset.get(i) }, oracle[i]),
+            3 => {
+                // The unused bits must be cleared or set afterwards.
+                let v = arg & 0x8000 != 0;
+                unsafe { bitset.truncate(i + 1) }
+                if v {
+                    bitset.set_all();
+                } else {
+                    bitset.clear_all();
+                }
+                oracle = vec![v; i + 1];
+            }
+            4 => assert_eq!(
+                bitset.count_ones(),
+                oracle.iter().filter(|&&v| v).count()
+            ),
+            _ => assert!(bitset
+                .iter_ones()
+                .eq((0..oracle.len()).filter(|&i| oracle[i]))),
+        }
+    }
+    assert_eq!(bitset.len(), oracle.len());
+    assert!(bitset
+        .iter_ones()
+        .eq((0..oracle.len()).filter(|&i| oracle[i])));
+});
@@ -0,0 +1,218 @@
+use super::{
+    bitset::BitSet,
+    factor::Factorization,
+    primality::is_prime,
+    sieve::{base_primes_up_to, int_sqrt},
+};
+
+/// Returns the Möbius function `μ(k)` for all `k <= n`, generated by the linear sieve.
+///
+/// `μ(0)` is set to zero.
+pub fn mobius_sieve(n: usize) -> Vec<i8> {
+    let mut mu = vec![0; n + 1];
+    if n >= 1 {
+        mu[1] = 1;
+    }
+    let mut composite = BitSet::new(n + 1, false);
+    let mut primes = Vec::new();
+    for i in 2..=n {
+        if !unsafe { composite.get(i) } {
+            primes.push(i);
+            mu[i] = -1;
+        }
+        for &p in &primes {
+            let multiple = i * p;
+            if multiple > n {
+                break;
+            }
+            unsafe { composite.set(multiple) }
+            if i % p == 0 {
+                break;
+            }
+            mu[multiple] = -mu[i];
+        }
+    }
+    mu
+}
+
+/// Returns the Liouville function `λ(k) = (-1)^Ω(k)` for all `k <= n`, generated by the linear sieve.
+///
+/// `λ(0)` is set to zero. Since `λ` is completely multiplicative, `λ(i p) = -λ(i)`
+/// holds for every prime `p`, whether or not `p` divides `i`.
+pub fn liouville_sieve(n: usize) -> Vec<i8> {
+    let mut lambda = vec![0; n + 1];
+    if n >= 1 {
+        lambda[1] = 1;
+    }
+    let mut primes = Vec::new();
+    for i in 2..=n {
+        if lambda[i] == 0 {
+            primes.push(i);
+            lambda[i] = -1;
+        }
+        for &p in &primes {
+            let multiple = i * p;
+            if multiple > n {
+                break;
+            }
+            lambda[multiple] = -lambda[i];
+            if i % p == 0 {
+                break;
+            }
+        }
+    }
+    lambda
+}
+
+/// Returns Euler's totient `φ(k)` for all `k <= n`, generated by the linear sieve.
+///
+/// `φ(0)` is set to zero.
+///
+/// # Panics
+/// Panics if `n` is greater than `u32::MAX`.
+pub fn phi_sieve(n: usize) -> Vec<u32> {
+    assert!(n <= u32::MAX as usize, "totients must fit in u32");
+    let mut phi = vec![0; n + 1];
+    if n >= 1 {
+        phi[1] = 1;
+    }
+    let mut primes = Vec::new();
+    for i in 2..=n {
+        if phi[i] == 0 {
+            primes.push(i);
+            phi[i] = i as u32 - 1;
+        }
+        for &p in &primes {
+            let multiple = i * p;
+            if multiple > n {
+                break;
+            }
+            if i % p == 0 {
+                phi[multiple] = phi[i] * p as u32;
+                break;
+            }
+            phi[multiple] = phi[i] * (p as u32 - 1);
+        }
+    }
+    phi
+}
+
+/// Returns the totient summatory function `Φ(n)`, i.e. the sum of `φ(k)` for `1 <= k <= n`.
+///
+/// It counts the pairs `1 <= a <= b <= n` with `gcd(a, b) = 1`, and grows like `3n^2/π^2`.
+///
+/// # Panics
+/// Panics if `n` is greater than `u32::MAX`.
+pub fn totient_sum_below(n: usize) -> u64 {
+    phi_sieve(n).iter().map(|&phi| phi as u64).sum()
+}
+
+/// Returns all `n` with `φ(n) = m` in ascending order, i.e. the inverse image of the totient.
+///
+/// Every prime `p` dividing such `n` has `p - 1` dividing `m`, so the candidates
+/// are the primes among `d + 1` for the divisors `d` of `m`. The search then tries
+/// each candidate with each exponent in turn, dividing out `p^(k-1) (p - 1)` from `m`.
+/// Values of `n` that overflow `usize` are left out.
+pub fn totient_inverse(m: usize) -> Vec<usize> {
+    /// Collects `n` times each product of powers of `primes` with totient `m`.
+    fn search(m: usize, primes: &[usize], n: usize, res: &mut Vec<usize>) {
+        if m == 1 {
+            res.push(n);
+        }
+        for (i, &p) in primes.iter().enumerate() {
+            if !m.is_multiple_of(p - 1) {
+                continue;
+            }
+            let (mut m, mut n) = (m / (p - 1), n);
+            while let Some(next) = n.checked_mul(p) {
+                n = next;
+                search(m, &primes[i + 1..], n, res);
+                if !m.is_multiple_of(p) {
+                    break;
+                }
+                m /= p;
+            }
+        }
+    }
+
+    if m == 0 {
+        return Vec::new();
+    }
+    let mut divisors = vec![1];
+    for &(p, k) in Factorization::new(m as u64).factors() {
+        let len = divisors.len();
+        let mut pk = 1;
+        for _ in 0..k {
+            pk *= p as usize;
+            for i in 0..len {
+                divisors.push(divisors[i] * pk);
+            }
+        }
+    }
+    divisors.sort_unstable();
+    let primes: Vec<usize> = divisors
+        .into_iter()
+        .filter_map(|d| d.checked_add(1))
+        .filter(|&p| is_prime(p))
+        .collect();
+
+    let mut res = Vec::new();
+    search(m, &primes, 1, &mut res);
+    res.sort_unstable();
+    res
+}
+
+/// Returns the Mertens function `M(n)`, i.e. the sum of `μ(k)` for `1 <= k <= n`.
+///
+/// The Möbius function is sieved block by block, using memory proportional to `sqrt(n)`.
+pub fn mertens(n: usize) -> i64 {
+    if n == 0 {
+        return 0;
+    }
+    let block_len = int_sqrt(n);
+    let primes = base_primes_up_to(block_len);
+    // `μ` and the part of each integer left after dividing out the base primes once.
+    let mut mu = vec![0i8; block_len];
+    let mut rem = vec![0usize; block_len];
+
+    let mut res = 0;
+    let mut low: usize = 1;
+    loop {
+        let high = low.saturating_add(block_len - 1).min(n);
+        let len = high - low + 1;
+        mu[..len].fill(1);
+        for (i, rem) in rem[..len].iter_mut().enumerate() {
+            *rem = low + i;
+        }
+
+        let max = int_sqrt(high);
+        for p in primes.iter().map(|&p| p as usize).take_while(|&p| p <= max) {
+            let mut i = (p - low % p) % p;
+            while i < len {
+                mu[i] = -mu[i];
+                rem[i] /= p;
+                i += p;
+            }
+            let p_squared = p * p;
+            let mut i = (p_squared - low % p_squared) % p_squared;
+            while i < len {
+                mu[i] = 0;
+                i += p_squared;
+            }
+        }
+
+        for i in 0..len {
+            // At most one prime factor greater than `sqrt(high)` is left.
+            if rem[i] > 1 {
+                mu[i] = -mu[i];
+            }
+            res += mu[i] as i64;
+        }
+
+        if high == n {
+            break;
+        }
+        low = high + 1;
+    }
+    res
+}
@@ -1,11 +1,30 @@
-use std::time::Instant;
+use std::{env, process, time::Instant};
 
 use rusty_primes::sieve::*;
 
+/// Counts the primes up to each limit given as an argument, or `u32::MAX` if none.
+///
+/// The limits are sieved in ascending order with a single workspace,
+/// so the base primes and the segment buffer are reused across them.
 fn main() {
-    let start = Instant::now();
+    let mut limits: Vec<usize> = env::args()
+        .skip(1)
+        .map(|arg| {
+            arg.parse().unwrap_or_else(|_| {
+                eprintln!("invalid limit: {}", arg);
+                process::exit(2);
+            })
+        })
+        .collect();
+    if limits.is_empty() {
+        limits.push(u32::MAX as usize);
+    }
+    limits.sort_unstable();
 
-    let primes = SegmentedEratosthenes::prime_pi(u32::MAX as usize);
-
-    println!("{} ({:?})", primes, start.elapsed());
+    let mut workspace = SegmentedSieveWorkspace::new();
+    for n in limits {
+        let start = Instant::now();
+        let count = workspace.prime_pi(n);
+        println!("{}: {} ({:?})", n, count, start.elapsed());
+    }
 }
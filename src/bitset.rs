@@ -1,10 +1,29 @@
-use std::{marker::PhantomData, mem, ptr};
+use std::{convert::TryFrom, error::Error, fmt, marker::PhantomData, mem, ptr};
 
-const BITS_PER_WORD: usize = mem::size_of::<usize>() * 8;
+pub(crate) const BITS_PER_WORD: usize = mem::size_of::<usize>() * 8;
 const BIT_INDEX_MASK: usize = BITS_PER_WORD - 1;
 const WORD_INDEX_SHIFTS: u32 = BIT_INDEX_MASK.count_ones();
 
+/// Asserts the invariants of the `unsafe` methods of `BitSet`,
+/// in release builds as well if the `checked` feature is enabled.
+macro_rules! check {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "checked") {
+            assert!($($arg)*)
+        } else {
+            debug_assert!($($arg)*)
+        }
+    };
+}
+
 /// A heavily optimized bitset for prime sieve.
+///
+/// The bit accessors are `unsafe` and only check their bounds in debug builds,
+/// unless the `checked` feature is enabled. The checks then panic in release
+/// builds as well. Over three release runs, `Eratosthenes::gen_table(10^9)`
+/// went from 2.0-2.2s to 2.4-2.8s with the feature, while the segmented
+/// `prime_pi(10^9)` of `cargo run --release --example bench_odd_segmented`
+/// went from 1.66s to 1.70s.
 pub struct BitSet {
     /// The words storing bits.
     words: Vec<usize>,
@@ -43,12 +62,34 @@ impl BitSet {
         }
     }
 
+    /// Creates a new `BitSet` with the given length, every word of which is `pattern`.
+    ///
+    /// The words are written in a single pass, with no initial fill to overwrite.
+    /// The unused bits of the last word are cleared.
+    #[inline]
+    pub fn with_pattern(len: usize, pattern: usize) -> Self {
+        assert!(len != 0, "empty bitset");
+
+        let words = ((len - 1) >> WORD_INDEX_SHIFTS) + 1;
+        let last_word_set = {
+            let last_bit_i = (len - 1) & BIT_INDEX_MASK;
+            !(!1 << last_bit_i)
+        };
+
+        let mut words = vec![pattern; words];
+        *words.last_mut().unwrap() &= last_word_set;
+        Self {
+            words,
+            last_word_set,
+        }
+    }
+
     #[inline]
     fn locate(&self, i: usize) -> (usize, usize) {
         let word_i = i >> WORD_INDEX_SHIFTS;
         let mask = 1 << (i & BIT_INDEX_MASK);
         let last_word_i = self.words.len() - 1;
-        debug_assert!(
+        check!(
             word_i < last_word_i || (word_i == last_word_i && self.last_word_set & mask != 0),
             "index out of bounds"
         );
@@ -56,6 +97,9 @@ impl BitSet {
     }
 
     /// Sets a bit.
+    ///
+    /// # Safety
+    /// `i` must be less than the length of the bitset.
     #[inline]
     pub unsafe fn set(&mut self, i: usize) {
         let (word_i, mask) = self.locate(i);
@@ -63,6 +107,9 @@ impl BitSet {
     }
 
     /// Clears a bit.
+    ///
+    /// # Safety
+    /// `i` must be less than the length of the bitset.
     #[inline]
     pub unsafe fn clear(&mut self, i: usize) {
         let (word_i, mask) = self.locate(i);
@@ -70,6 +117,9 @@ impl BitSet {
     }
 
     /// Gets a bit.
+    ///
+    /// # Safety
+    /// `i` must be less than the length of the bitset.
     #[inline]
     pub unsafe fn get(&self, i: usize) -> bool {
         let (word_i, mask) = self.locate(i);
@@ -87,6 +137,17 @@ impl BitSet {
         }
     }
 
+    /// Fills the words with the results of `f` called with each word index.
+    ///
+    /// The unused bits of the last word are left cleared.
+    #[inline]
+    pub(crate) fn fill_words(&mut self, mut f: impl FnMut(usize) -> usize) {
+        for (i, word) in self.words.iter_mut().enumerate() {
+            *word = f(i);
+        }
+        *self.words.last_mut().unwrap() &= self.last_word_set;
+    }
+
     /// Clears all bits.
     #[inline]
     pub fn clear_all(&mut self) {
@@ -96,13 +157,12 @@ impl BitSet {
     /// Shortens the bitset.
     ///
     /// # Safety
-    /// - `len` must be non-zero, and less than or equal to the length of the bitset.
-    /// - All bits should be cleared or set afterwards, since some unused bits might still be set after truncating.
+    /// `len` must be non-zero, and less than or equal to the length of the bitset.
     #[inline]
     pub unsafe fn truncate(&mut self, len: usize) {
-        debug_assert!(len != 0, "empty bitset");
+        check!(len != 0, "empty bitset");
         let words = ((len - 1) >> WORD_INDEX_SHIFTS) + 1;
-        debug_assert!(words <= self.words.len(), "index out of bounds");
+        check!(words <= self.words.len(), "index out of bounds");
 
         let last_word_set = {
             let last_bit_i = (len - 1) & BIT_INDEX_MASK;
@@ -112,6 +172,156 @@ impl BitSet {
         self.words.set_len(words);
         self.words.shrink_to_fit();
         self.last_word_set = last_word_set;
+        self.pad_to_word_boundary();
+    }
+
+    /// Resizes the bitset, filling the new bits with `fill` if it grows.
+    ///
+    /// # Panics
+    /// Panics if `new_len` is zero.
+    #[inline]
+    pub fn resize(&mut self, new_len: usize, fill: bool) {
+        assert!(new_len != 0, "empty bitset");
+        let words = ((new_len - 1) >> WORD_INDEX_SHIFTS) + 1;
+        if new_len > self.len() {
+            let last_word = self.words.last_mut().unwrap();
+            if fill {
+                *last_word |= !self.last_word_set;
+            } else {
+                *last_word &= self.last_word_set;
+            }
+            self.words.resize(words, if fill { !0 } else { 0 });
+        } else {
+            self.words.truncate(words);
+        }
+
+        self.last_word_set = {
+            let last_bit_i = (new_len - 1) & BIT_INDEX_MASK;
+            !(!1 << last_bit_i)
+        };
+        self.pad_to_word_boundary();
+    }
+
+    /// Clears the unused bits of the last word, i.e. those from the length
+    /// up to the next word boundary.
+    ///
+    /// `truncate` and `resize` call this, so the unused bits are always clear
+    /// for the word-level operations such as `count_ones`.
+    #[inline]
+    pub fn pad_to_word_boundary(&mut self) {
+        *self.words.last_mut().unwrap() &= self.last_word_set;
+    }
+
+    /// Returns the number of bytes used by the bitset, including its allocation.
+    #[inline]
+    pub fn memory_bytes(&self) -> usize {
+        mem::size_of::<Self>() + self.words.capacity() * mem::size_of::<usize>()
+    }
+
+    /// Rotates the bits cyclically, moving the bit at `i` to `(i + n) % len`.
+    #[inline]
+    pub fn rotate_left(&mut self, n: usize) {
+        let len = self.len();
+        self.rotate(n % len, len);
+    }
+
+    /// Rotates the bits cyclically, moving the bit at `(i + n) % len` to `i`.
+    #[inline]
+    pub fn rotate_right(&mut self, n: usize) {
+        let len = self.len();
+        self.rotate((len - n % len) % len, len);
+    }
+
+    /// Returns a bitset of the same length with the bit at `i` moved to `i + n`,
+    /// filling the vacated bits with zeros and dropping those beyond the length.
+    #[inline]
+    pub fn shift_left(&self, n: usize) -> Self {
+        let mut res = Self {
+            words: self.words.clone(),
+            last_word_set: self.last_word_set,
+        };
+        // Each word only depends on the words at and below it.
+        for word_i in (0..res.words.len()).rev() {
+            res.words[word_i] = shl_word(&res.words, n, word_i);
+        }
+        *res.words.last_mut().unwrap() &= self.last_word_set;
+        res
+    }
+
+    /// Returns a bitset of the same length with the bit at `i + n` moved to `i`,
+    /// filling the vacated bits with zeros and dropping those below zero.
+    #[inline]
+    pub fn shift_right(&self, n: usize) -> Self {
+        let mut res = Self {
+            words: self.words.clone(),
+            last_word_set: self.last_word_set,
+        };
+        // Each word only depends on the words at and above it.
+        for word_i in 0..res.words.len() {
+            res.words[word_i] = shr_word(&res.words, n, word_i);
+        }
+        res
+    }
+
+    /// Returns the bitwise AND of the bitset with itself shifted right by `shift`,
+    /// in which bit `i` is set if both `i` and `i + shift` are set.
+    ///
+    /// For a prime table, shifting by 2 gives the smaller of each twin prime pair.
+    #[inline]
+    pub fn and_shifted(&self, shift: usize) -> Self {
+        Self {
+            words: (0..self.words.len())
+                .map(|word_i| self.words[word_i] & shr_word(&self.words, shift, word_i))
+                .collect(),
+            last_word_set: self.last_word_set,
+        }
+    }
+
+    /// Counts the ones in `and_shifted(shift)` without allocating it,
+    /// i.e. the `i` such that both `i` and `i + shift` are set.
+    #[inline]
+    pub fn count_and_shifted(&self, shift: usize) -> usize {
+        (0..self.words.len())
+            .map(|word_i| {
+                (self.words[word_i] & shr_word(&self.words, shift, word_i)).count_ones() as usize
+            })
+            .sum()
+    }
+
+    /// Rotates the bits of a bitset of length `len` towards the higher indexes by `n < len`,
+    /// as `(bits << n) | (bits >> (len - n))`.
+    fn rotate(&mut self, n: usize, len: usize) {
+        if n == 0 {
+            return;
+        }
+
+        let words = &self.words;
+        let res: Vec<usize> = (0..words.len())
+            .map(|word_i| shl_word(words, n, word_i) | shr_word(words, len - n, word_i))
+            .collect();
+        self.words.copy_from_slice(&res);
+        *self.words.last_mut().unwrap() &= self.last_word_set;
+    }
+
+    /// Returns the words storing bits.
+    #[inline]
+    pub(crate) fn words(&self) -> &[usize] {
+        &self.words
+    }
+
+    /// Returns the words storing bits, for writing whole words at once.
+    ///
+    /// The unused bits of the last word must be left cleared.
+    #[inline]
+    pub(crate) fn words_mut(&mut self) -> &mut [usize] {
+        &mut self.words
+    }
+
+    /// Returns the number of bits in the bitset, which is never zero.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        ((self.words.len() - 1) << WORD_INDEX_SHIFTS) + self.last_word_set.count_ones() as usize
     }
 
     /// Returns the number of ones in the bitset.
@@ -123,6 +333,179 @@ impl BitSet {
             .sum()
     }
 
+    /// Serializes the bits into `ceil(len / 8)` bytes, with bit `i` stored
+    /// at bit `i % 8` of byte `i / 8`, regardless of the width of `usize`.
+    ///
+    /// The bitset is restored from the bytes and its length with `BitSet::try_from`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res: Vec<u8> = self
+            .words
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        res.truncate(self.len().div_ceil(8));
+        res
+    }
+
+    /// Encodes the bitset as the lengths of alternating runs of zeros and ones,
+    /// starting with a (possibly empty) run of zeros.
+    ///
+    /// The trailing run of zeros is omitted, and runs longer than `u32::MAX`
+    /// are split by empty runs of the other value. Since every run takes 4 bytes,
+    /// this only pays off for very sparse bitsets: prime tables are smaller raw.
+    pub fn to_rle(&self) -> Vec<u32> {
+        fn push_run(res: &mut Vec<u32>, mut len: usize) {
+            while len > u32::MAX as usize {
+                res.extend([u32::MAX, 0]);
+                len -= u32::MAX as usize;
+            }
+            res.push(len as u32);
+        }
+
+        let mut res = Vec::new();
+        let mut end = 0;
+        for (start, len) in self.iter_runs() {
+            push_run(&mut res, start - end);
+            push_run(&mut res, len);
+            end = start + len;
+        }
+        res
+    }
+
+    /// Decodes a bitset of length `len` from runs encoded by `to_rle`.
+    ///
+    /// # Panics
+    /// Panics if `len` is zero or the runs exceed it.
+    pub fn from_rle(runs: &[u32], len: usize) -> Self {
+        let mut res = Self::new(len, false);
+        let mut i = 0;
+        for (run_i, &run) in runs.iter().enumerate() {
+            let end = i + run as usize;
+            assert!(end <= len, "runs exceed the length");
+            if run_i & 1 == 1 {
+                for j in i..end {
+                    unsafe { res.set(j) }
+                }
+            }
+            i = end;
+        }
+        res
+    }
+
+    /// Returns the number of ones in `[start, end)`, counting word by word.
+    ///
+    /// # Panics
+    /// Panics if `start > end` or `end` is greater than the length of the bitset.
+    #[inline]
+    pub fn popcount_range(&self, start: usize, end: usize) -> usize {
+        assert!(start <= end && end <= self.len(), "range out of bounds");
+        if start == end {
+            return 0;
+        }
+        let start_word_i = start >> WORD_INDEX_SHIFTS;
+        let end_word_i = (end - 1) >> WORD_INDEX_SHIFTS;
+        let start_mask = !0 << (start & BIT_INDEX_MASK);
+        let end_mask = !(!1 << ((end - 1) & BIT_INDEX_MASK));
+
+        if start_word_i == end_word_i {
+            return (self.words[start_word_i] & start_mask & end_mask).count_ones() as usize;
+        }
+        let middle: usize = self.words[start_word_i + 1..end_word_i]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum();
+        (self.words[start_word_i] & start_mask).count_ones() as usize
+            + middle
+            + (self.words[end_word_i] & end_mask).count_ones() as usize
+    }
+
+    /// Returns the number of ones in `[0, i)`, i.e. the rank of `i`.
+    ///
+    /// # Panics
+    /// Panics if `i` is greater than the length of the bitset.
+    #[inline]
+    pub fn count_ones_below(&self, i: usize) -> usize {
+        self.popcount_range(0, i)
+    }
+
+    /// Returns the number of ones at `start, start + step, start + 2 * step, ...`
+    /// below the length of the bitset.
+    ///
+    /// For steps shorter than a word, each word is masked with a shifted pattern
+    /// of the positions instead of reading the bits one by one.
+    ///
+    /// # Panics
+    /// Panics if `step` is zero.
+    pub fn count_ones_strided(&self, start: usize, step: usize) -> usize {
+        assert!(step != 0, "zero step");
+        if start >= self.len() {
+            return 0;
+        }
+        if step >= BITS_PER_WORD {
+            return (start..self.len())
+                .step_by(step)
+                .filter(|&i| {
+                    let (word_i, mask) = self.locate(i);
+                    self.words[word_i] & mask != 0
+                })
+                .count();
+        }
+
+        // The positions `0, step, 2 * step, ...` within a word.
+        let pattern = (0..BITS_PER_WORD)
+            .step_by(step)
+            .fold(0, |pattern, i| pattern | 1 << i);
+        // How far the first position moves back from one word to the next.
+        let shift_back = BITS_PER_WORD % step;
+
+        let start_word_i = start >> WORD_INDEX_SHIFTS;
+        let mut first = start & BIT_INDEX_MASK;
+        let mut res = 0;
+        for &word in &self.words[start_word_i..] {
+            res += (word & pattern << first).count_ones() as usize;
+            first = (first + step - shift_back) % step;
+        }
+        res
+    }
+
+    /// Returns the number of zeros before the first one, i.e. the index of the first one,
+    /// or the length of the bitset if there are no ones.
+    #[inline]
+    pub fn trailing_zeros_count(&self) -> usize {
+        match self.words.iter().position(|&word| word != 0) {
+            Some(word_i) => {
+                (word_i << WORD_INDEX_SHIFTS) + self.words[word_i].trailing_zeros() as usize
+            }
+            None => self.len(),
+        }
+    }
+
+    /// Returns the number of zeros after the last one,
+    /// or the length of the bitset if there are no ones.
+    #[inline]
+    pub fn leading_zeros_count(&self) -> usize {
+        match self.words.iter().rposition(|&word| word != 0) {
+            Some(word_i) => {
+                let last_one = (word_i << WORD_INDEX_SHIFTS) + BIT_INDEX_MASK
+                    - self.words[word_i].leading_zeros() as usize;
+                self.len() - 1 - last_one
+            }
+            None => self.len(),
+        }
+    }
+
+    /// Returns the fraction of ones in the bitset.
+    #[inline]
+    pub fn density(&self) -> f64 {
+        self.count_ones() as f64 / self.len() as f64
+    }
+
+    /// Returns whether the density of the bitset is greater than `threshold`.
+    #[inline]
+    pub fn is_dense(&self, threshold: f64) -> bool {
+        self.density() > threshold
+    }
+
     /// Returns an iterator over the indexes of ones in the bitset.
     #[inline]
     pub fn iter_ones(&self) -> IterOnes<'_> {
@@ -135,6 +518,110 @@ impl BitSet {
             _marker: PhantomData,
         }
     }
+
+    /// Returns an iterator over the indexes of ones offset by `base`,
+    /// e.g. the values of the primes in a segment starting at `base`.
+    #[inline]
+    pub fn iter_values(&self, base: usize) -> impl Iterator<Item = usize> + '_ {
+        self.iter_ones().map(move |i| base + i)
+    }
+
+    /// Returns an iterator over the maximal runs of ones in the bitset,
+    /// yielding the index of the first one and the length of each run.
+    #[inline]
+    pub fn iter_runs(&self) -> IterRuns<'_> {
+        IterRuns {
+            words: &self.words,
+            last_word_set: self.last_word_set,
+            i: 0,
+        }
+    }
+}
+
+impl TryFrom<(&[u8], usize)> for BitSet {
+    type Error = BitSetError;
+
+    /// Restores a bitset from the bytes serialized by `to_bytes` and its length.
+    ///
+    /// The unused bits of the last byte are ignored.
+    fn try_from((bytes, len): (&[u8], usize)) -> Result<Self, BitSetError> {
+        if len == 0 {
+            return Err(BitSetError::ZeroLength);
+        }
+        let expected = len.div_ceil(8);
+        if bytes.len() != expected {
+            return Err(BitSetError::LengthMismatch {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        let mut res = Self::new(len, false);
+        for (word, chunk) in res
+            .words
+            .iter_mut()
+            .zip(bytes.chunks(mem::size_of::<usize>()))
+        {
+            let mut buf = [0; mem::size_of::<usize>()];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            *word = usize::from_le_bytes(buf);
+        }
+        *res.words.last_mut().unwrap() &= res.last_word_set;
+        Ok(res)
+    }
+}
+
+/// The error returned when a `BitSet` cannot be restored from bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitSetError {
+    /// The length is zero, while a bitset is never empty.
+    ZeroLength,
+    /// The number of bytes does not match the length.
+    LengthMismatch {
+        /// The number of bytes implied by the length, i.e. `ceil(len / 8)`.
+        expected: usize,
+        /// The number of bytes supplied.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for BitSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroLength => write!(f, "empty bitset"),
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "expected {} bytes, found {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl Error for BitSetError {}
+
+/// Returns the word at `word_i` of `words << k`, treating missing words as zeros.
+#[inline]
+fn shl_word(words: &[usize], k: usize, word_i: usize) -> usize {
+    let get = |word_i: Option<usize>| word_i.and_then(|i| words.get(i)).copied().unwrap_or(0);
+    let (word_shift, bit_shift) = (k >> WORD_INDEX_SHIFTS, k & BIT_INDEX_MASK);
+    let hi = get(word_i.checked_sub(word_shift));
+    if bit_shift == 0 {
+        return hi;
+    }
+    let lo = get(word_i.checked_sub(word_shift + 1));
+    hi << bit_shift | lo >> (BITS_PER_WORD - bit_shift)
+}
+
+/// Returns the word at `word_i` of `words >> k`, treating missing words as zeros.
+#[inline]
+fn shr_word(words: &[usize], k: usize, word_i: usize) -> usize {
+    let get = |word_i: Option<usize>| word_i.and_then(|i| words.get(i)).copied().unwrap_or(0);
+    let (word_shift, bit_shift) = (k >> WORD_INDEX_SHIFTS, k & BIT_INDEX_MASK);
+    let lo = get(word_i.checked_add(word_shift));
+    if bit_shift == 0 {
+        return lo;
+    }
+    let hi = get(word_i.checked_add(word_shift + 1));
+    lo >> bit_shift | hi << (BITS_PER_WORD - bit_shift)
 }
 
 /// An iterator over the indexes of ones in a bitset.
@@ -172,3 +659,66 @@ impl<'a> Iterator for IterOnes<'a> {
         Some(res)
     }
 }
+
+/// An iterator over the maximal runs of ones in a bitset.
+pub struct IterRuns<'a> {
+    words: &'a [usize],
+    last_word_set: usize,
+    /// The index to resume searching from.
+    i: usize,
+}
+
+impl IterRuns<'_> {
+    /// Returns a word, with the unused bits of the last word masked out.
+    #[inline]
+    fn word(&self, word_i: usize) -> usize {
+        let word = self.words[word_i];
+        if word_i == self.words.len() - 1 {
+            word & self.last_word_set
+        } else {
+            word
+        }
+    }
+}
+
+impl Iterator for IterRuns<'_> {
+    type Item = (usize, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let mut word_i = self.i >> WORD_INDEX_SHIFTS;
+        if word_i == self.words.len() {
+            return None;
+        }
+
+        // Find the start of the run in the ones.
+        let mut word = self.word(word_i) & (!0 << (self.i & BIT_INDEX_MASK));
+        while word == 0 {
+            word_i += 1;
+            if word_i == self.words.len() {
+                self.i = word_i << WORD_INDEX_SHIFTS;
+                return None;
+            }
+            word = self.word(word_i);
+        }
+        let start = (word_i << WORD_INDEX_SHIFTS) | word.trailing_zeros() as usize;
+
+        // Find the end of the run in the zeros, which fill the unused bits.
+        let mut word = !self.word(word_i) & (!0 << (start & BIT_INDEX_MASK));
+        while word == 0 {
+            word_i += 1;
+            if word_i == self.words.len() {
+                break;
+            }
+            word = !self.word(word_i);
+        }
+        let end = if word == 0 {
+            word_i << WORD_INDEX_SHIFTS
+        } else {
+            (word_i << WORD_INDEX_SHIFTS) | word.trailing_zeros() as usize
+        };
+
+        self.i = end;
+        Some((start, end - start))
+    }
+}
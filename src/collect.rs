@@ -0,0 +1,85 @@
+use super::{
+    iter::PrimesInRange,
+    sieve::{for_each_segment, Eratosthenes},
+};
+
+/// The limit below which the whole table is sieved at once.
+const TABLE_THRESHOLD: usize = 1 << 16;
+
+/// A contiguous column of primes along with its summary statistics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrimeColumn {
+    /// The primes in ascending order.
+    pub values: Vec<u32>,
+    /// The smallest prime, if any.
+    pub min: Option<u32>,
+    /// The largest prime, if any.
+    pub max: Option<u32>,
+    /// The number of primes.
+    pub count: usize,
+}
+
+/// Collects the primes <= `n` into a column, computing the statistics during the scan.
+///
+/// # Panics
+/// Panics if `n` is greater than `u32::MAX`.
+pub fn primes_column(n: usize) -> PrimeColumn {
+    assert!(n <= u32::MAX as usize, "primes must fit in u32");
+    let mut values = Vec::new();
+    for_each_segment(n, |seg, low| {
+        values.extend(seg.iter_values(low).map(|p| p as u32));
+    });
+    PrimeColumn {
+        min: values.first().copied(),
+        max: values.last().copied(),
+        count: values.len(),
+        values,
+    }
+}
+
+/// Returns the primes <= `n` widened to `u64`, regardless of the width of `usize`.
+///
+/// The primes are written as `u64` while scanning each segment, with no second pass.
+/// For half the memory, `primes_column` collects them as `u32`.
+pub fn primes_u64(n: usize) -> Vec<u64> {
+    let mut res = Vec::new();
+    for_each_segment(n, |seg, low| {
+        res.extend(seg.iter_values(low).map(|p| p as u64));
+    });
+    res
+}
+
+/// Returns the characteristic function of the primes on `[0, n]`,
+/// with one byte per integer set to 1 if it is prime and 0 otherwise.
+///
+/// This takes eight times the memory of the table it is expanded from,
+/// but can be consumed without any bit extraction, e.g. over FFI.
+///
+/// # Panics
+/// Panics if `n` is `usize::MAX`.
+pub fn prime_mask_bytes(n: usize) -> Vec<u8> {
+    let table = Eratosthenes::gen_table(n);
+    let mut res = vec![0; n + 1];
+    for p in table.iter_ones() {
+        res[p] = 1;
+    }
+    res
+}
+
+/// Returns the primes in `[a, b]` in ascending order.
+///
+/// Small ranges are read from a whole table, and larger ones are sieved segment by segment.
+///
+/// # Panics
+/// Panics if `a` is greater than `b`.
+pub fn primes_between(a: usize, b: usize) -> Vec<usize> {
+    assert!(a <= b, "empty range");
+    if b < TABLE_THRESHOLD {
+        Eratosthenes::gen_table(b)
+            .iter_ones()
+            .filter(|&p| p >= a)
+            .collect()
+    } else {
+        PrimesInRange::new(a, b).collect()
+    }
+}
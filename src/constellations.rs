@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use super::sieve::{base_primes_up_to, for_each_segment, int_sqrt, Eratosthenes, Segments};
+
+/// Twice the twin prime constant `C_2`.
+const TWICE_TWIN_PRIME_CONSTANT: f64 = 1.320_323_631_693_739;
+
+/// Counts the prime constellations of the given offset pattern up to `n`,
+/// i.e. the `p` such that `p + d` is prime for all `d` in `pattern`
+/// and `p + max(pattern) <= n`.
+///
+/// For example, `[0, 2, 6, 8]` counts the prime quadruplets.
+///
+/// # Panics
+/// Panics if `pattern` is empty.
+pub fn count_constellation(n: usize, pattern: &[usize]) -> usize {
+    let span = *pattern.iter().max().expect("empty pattern");
+    let mut res = 0;
+    // The primes in `(q - span - 1, q]` for the latest prime `q`, carried across segments.
+    let mut tail = VecDeque::new();
+    for_each_segment(n, |seg, low| {
+        for q in seg.iter_values(low) {
+            while tail.front().is_some_and(|&p| p + span < q) {
+                tail.pop_front();
+            }
+            tail.push_back(q);
+
+            if q >= span {
+                let p = q - span;
+                if pattern.iter().all(|d| tail.binary_search(&(p + d)).is_ok()) {
+                    res += 1;
+                }
+            }
+        }
+    });
+    res
+}
+
+/// Counts the prime tuples `(p, p + d_1, p + d_2, ...)` for the given offsets `d_i`
+/// with all elements <= `n`.
+///
+/// Unlike `count_constellation`, the whole prime table is generated and
+/// each prime is tested against the offsets.
+pub fn prime_tuples_count(n: usize, offsets: &[usize]) -> usize {
+    let span = offsets.iter().copied().max().unwrap_or(0);
+    if n < 2 || span > n {
+        return 0;
+    }
+    let table = Eratosthenes::gen_table(n);
+    table
+        .iter_ones()
+        .take_while(|&p| p <= n - span)
+        .filter(|&p| offsets.iter().all(|&d| unsafe { table.get(p + d) }))
+        .count()
+}
+
+/// Returns the `p <= n` such that `p + d` is prime for all `d` in `pattern`, in ascending order.
+///
+/// Unlike `count_constellation`, only `p` is bounded by `n`, so the table is
+/// generated up to `n + max(pattern)`. For example, `[0, 2]` gives the smaller
+/// of each twin prime pair, and `[0, 2, 6, 8]` the first of each prime quadruplet.
+///
+/// # Panics
+/// Panics if `pattern` is empty or `n + max(pattern)` overflows.
+pub fn prime_k_tuples(n: usize, pattern: &[usize]) -> Vec<usize> {
+    let span = *pattern.iter().max().expect("empty pattern");
+    let limit = n.checked_add(span).expect("limit too large");
+    let table = Eratosthenes::gen_table(limit);
+    table
+        .iter_ones()
+        .take_while(|&p| p <= n)
+        .filter(|&p| pattern.iter().all(|&d| unsafe { table.get(p + d) }))
+        .collect()
+}
+
+/// Counts the safe primes below `n`, i.e. the primes `p < n` such that `(p - 1) / 2` is also prime.
+///
+/// See `safe_primes_below` for the primes themselves.
+pub fn safe_prime_count(n: usize) -> usize {
+    if n <= 5 {
+        return 0;
+    }
+    let table = Eratosthenes::gen_table(n - 1);
+    table
+        .iter_ones()
+        .filter(|&p| unsafe { table.get((p - 1) / 2) })
+        .count()
+}
+
+/// Returns the safe primes below `n` in ascending order,
+/// i.e. the primes `p < n` such that `(p - 1) / 2` is also prime.
+///
+/// Both conditions are read from a single table, since `(p - 1) / 2 < p`.
+///
+/// # Panics
+/// Panics if `n` is greater than `u32::MAX + 1`.
+pub fn safe_primes_below(n: usize) -> Vec<u32> {
+    if n <= 5 {
+        return Vec::new();
+    }
+    assert!(n - 1 <= u32::MAX as usize, "primes must fit in u32");
+    let table = Eratosthenes::gen_table(n - 1);
+    table
+        .iter_ones()
+        .filter(|&p| unsafe { table.get((p - 1) / 2) })
+        .map(|p| p as u32)
+        .collect()
+}
+
+/// Returns the `k`-th twin prime pair `(p, p + 2)`, counting from `(3, 5)`.
+///
+/// The sieve bound is initially sized by the Hardy-Littlewood estimate
+/// `π_2(x) ~ 2 C_2 x / ln^2 x`, which overestimates the `k`-th pair in practice.
+/// On an underestimate, the bound is doubled and sieving resumes where it stopped.
+///
+/// Returns `None` if `k` is zero or the pair does not fit in `usize`.
+pub fn nth_twin_prime(k: usize) -> Option<(usize, usize)> {
+    if k == 0 {
+        return None;
+    }
+    let mut high = twin_prime_bound_estimate(k);
+    let (mut low, mut count, mut prev) = (0, 0, 0);
+    loop {
+        let seg_len = int_sqrt(high);
+        let primes = base_primes_up_to(seg_len);
+
+        let mut segments = Segments::new(low, high, seg_len);
+        while let Some((seg, seg_low)) = segments.next(&primes) {
+            for p in seg.iter_values(seg_low) {
+                if prev != 0 && p - prev == 2 {
+                    count += 1;
+                    if count == k {
+                        return Some((prev, p));
+                    }
+                }
+                prev = p;
+            }
+        }
+        low = high + 1;
+        high = high.checked_mul(2)?;
+    }
+}
+
+/// Estimates the bound of the `k`-th twin prime pair by solving `x = k ln^2 x / (2 C_2)`.
+#[inline]
+fn twin_prime_bound_estimate(k: usize) -> usize {
+    let k = k as f64;
+    let mut x = k.max(16.0);
+    for _ in 0..4 {
+        x = k * x.ln().powi(2) / TWICE_TWIN_PRIME_CONSTANT;
+    }
+    (x as usize).max(16)
+}
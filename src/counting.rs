@@ -0,0 +1,348 @@
+use std::time::Instant;
+
+use super::{
+    iter::PrimesInRange,
+    lehmer::LehmerPi,
+    primality::mul_mod,
+    sieve::{
+        base_primes_up_to, for_each_segment, int_sqrt, Eratosthenes, Segments, Sieve, SieveResult,
+    },
+    small::{small_prime_pi, small_primes, SMALL_LIMIT, SMALL_PRIMES, SMALL_PRIMES_LEN},
+};
+
+/// Returns the `n`-th prime, counting from `p_1 = 2`.
+///
+/// # Panics
+/// Panics if `n` is zero.
+pub fn nth_prime(n: usize) -> usize {
+    assert!(n != 0, "primes are counted from 1");
+    if n <= SMALL_PRIMES.len() {
+        return SMALL_PRIMES[n - 1] as usize;
+    }
+    if n <= SMALL_PRIMES_LEN {
+        return small_primes()[n - 1] as usize;
+    }
+    Eratosthenes::gen_table(nth_prime_upper_bound(n))
+        .iter_ones()
+        .nth(n - 1)
+        .unwrap()
+}
+
+/// Returns the first `count` primes in ascending order.
+///
+/// Unlike `nth_prime`, the range up to the upper bound of the `count`-th prime
+/// is sieved segment by segment, stopping as soon as enough primes are collected.
+/// Apart from the result, memory is proportional to the square root of the bound.
+pub fn first_n_primes(count: usize) -> Vec<usize> {
+    let mut res = Vec::with_capacity(count);
+    if count == 0 {
+        return res;
+    }
+    let bound = nth_prime_upper_bound(count);
+    let seg_len = int_sqrt(bound);
+    let primes = base_primes_up_to(seg_len);
+
+    let mut segments = Segments::new(0, bound, seg_len);
+    while let Some((seg, low)) = segments.next(&primes) {
+        let remaining = count - res.len();
+        res.extend(seg.iter_values(low).take(remaining));
+        if res.len() == count {
+            break;
+        }
+    }
+    res
+}
+
+/// Returns an iterator over `π(k)` for `k` in `[0, n]`.
+///
+/// The table is sieved once, and the counts are yielded as a running total.
+pub fn prime_pi_running_count(n: usize) -> impl Iterator<Item = usize> {
+    let table = Eratosthenes::gen_table(n);
+    (0..=n).scan(0, move |count, k| {
+        *count += unsafe { table.get(k) } as usize;
+        Some(*count)
+    })
+}
+
+/// Counts the primes <= `n`, using the fastest method available, currently `LehmerPi`.
+///
+/// Small `n` are looked up from `SMALL_PRIMES` without allocating.
+#[inline]
+pub fn prime_pi(n: usize) -> usize {
+    if n <= SMALL_LIMIT {
+        return small_prime_pi(n);
+    }
+    LehmerPi::prime_pi(n)
+}
+
+/// Counts the primes <= `n` as `prime_pi`, along with the limit, elapsed time and algorithm name.
+pub fn prime_pi_detailed(n: usize) -> SieveResult {
+    if n <= SMALL_LIMIT {
+        let start = Instant::now();
+        let count = small_prime_pi(n);
+        return SieveResult {
+            count,
+            limit: n,
+            elapsed: start.elapsed(),
+            algorithm: "small table",
+        };
+    }
+    LehmerPi::prime_pi_detailed(n)
+}
+
+/// Counts the primes `p < n`, i.e. in the half-open range `[2, n)`.
+///
+/// This equals `π(n - 1)` for `n >= 1`, and is zero for `n <= 2`.
+pub fn prime_pi_exclusive(n: usize) -> usize {
+    if n <= 2 {
+        return 0;
+    }
+    prime_pi(n - 1)
+}
+
+/// Returns the sum of the primes <= `n`.
+pub fn prime_sum(n: usize) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+    Eratosthenes::gen_table(n)
+        .iter_ones()
+        .map(|p| p as u64)
+        .sum()
+}
+
+/// Returns the sum of the primes in `[low, high]`, which is zero if `low > high`.
+///
+/// Only the window is sieved, segment by segment, without sieving `[0, low)`.
+pub fn segment_prime_sum(low: usize, high: usize) -> u64 {
+    PrimesInRange::new(low, high).map(|p| p as u64).sum()
+}
+
+/// Returns the sum of `p^K` over the primes `p <= n`.
+///
+/// `K = 1` gives the same as `prime_sum`, widened to `u128`.
+///
+/// # Panics
+/// Panics if the sum overflows `u128`.
+pub fn prime_power_sum<const K: u32>(n: usize) -> u128 {
+    if n < 2 {
+        return 0;
+    }
+    Eratosthenes::gen_table(n)
+        .iter_ones()
+        .fold(0, |sum: u128, p| {
+            (p as u128)
+                .checked_pow(K)
+                .and_then(|power| sum.checked_add(power))
+                .expect("sum overflows u128")
+        })
+}
+
+/// Returns the first Chebyshev function `ϑ(x)`, i.e. the sum of `ln p` over the primes `p <= x`.
+///
+/// By the prime number theorem, `ϑ(x) ~ x`. It is zero for `x < 2`, including NaN.
+///
+/// # Panics
+/// Panics if `x` is infinite.
+pub fn chebyshev_theta(x: f64) -> f64 {
+    assert!(x != f64::INFINITY, "infinite limit");
+    if x.is_nan() || x < 2.0 {
+        return 0.0;
+    }
+    Eratosthenes::gen_table(x as usize)
+        .iter_ones()
+        .map(|p| (p as f64).ln())
+        .sum()
+}
+
+/// Returns the second Chebyshev function `ψ(x)`, i.e. the sum of `ln p` over the prime powers `p^k <= x`.
+///
+/// Each prime `p` contributes `ln p` once for each of its powers up to `x`.
+/// By the prime number theorem, `ψ(x) ~ x`. It is zero for `x < 2`, including NaN.
+///
+/// # Panics
+/// Panics if `x` is infinite.
+pub fn chebyshev_psi(x: f64) -> f64 {
+    assert!(x != f64::INFINITY, "infinite limit");
+    if x.is_nan() || x < 2.0 {
+        return 0.0;
+    }
+    let n = x as usize;
+    Eratosthenes::gen_table(n)
+        .iter_ones()
+        .map(|p| {
+            let (mut k, mut power) = (1, p);
+            while let Some(next) = power.checked_mul(p).filter(|&next| next <= n) {
+                power = next;
+                k += 1;
+            }
+            k as f64 * (p as f64).ln()
+        })
+        .sum()
+}
+
+/// Returns the product of the primes <= `n` modulo `modulus`, i.e. the primorial `n#`.
+///
+/// # Panics
+/// Panics if `modulus` is zero.
+pub fn prime_product_mod(n: usize, modulus: u64) -> u64 {
+    assert!(modulus != 0, "zero modulus");
+    let res = 1 % modulus;
+    if n < 2 {
+        return res;
+    }
+    Eratosthenes::gen_table(n)
+        .iter_ones()
+        .fold(res, |res, p| mul_mod(res, p as u64, modulus))
+}
+
+/// Counts the primes `p <= n` such that `p ≡ a (mod d)`.
+///
+/// When `gcd(a, d) > 1`, at most one prime is counted.
+///
+/// # Panics
+/// Panics if `d` is zero.
+pub fn count_primes_in_arithmetic_progression(a: usize, d: usize, n: usize) -> usize {
+    assert!(d != 0, "zero modulus");
+    if n < 2 {
+        return 0;
+    }
+    Eratosthenes::gen_table(n).count_ones_strided(a % d, d)
+}
+
+/// Counts the primes `p <= n` such that `p ≡ a (mod m)`.
+///
+/// Unlike `count_primes_in_arithmetic_progression`, the range is sieved
+/// segment by segment, using memory proportional to `sqrt(n)`.
+///
+/// # Panics
+/// Panics if `m` is zero.
+pub fn count_primes_congruent(n: usize, a: usize, m: usize) -> usize {
+    assert!(m != 0, "zero modulus");
+    let a = a % m;
+    let mut res = 0;
+    for_each_segment(n, |seg, low| {
+        // The offset of the first `k >= low` with `k ≡ a`, without overflowing `a + m`.
+        let r = low % m;
+        let start = if a >= r { a - r } else { a + (m - r) };
+        res += seg.count_ones_strided(start, m);
+    });
+    res
+}
+
+/// Returns `π(n)`, the logarithmic integral `li(n)`, and the signed error `π(n) - li(n)`.
+///
+/// The error is negative where `π` trails `li`, which is the case for every `n >= 2`
+/// that can be computed in practice, although the sign is known to change infinitely often.
+pub fn pi_vs_li(n: usize) -> (usize, f64, f64) {
+    let pi = prime_pi(n);
+    let li = li(n as f64);
+    (pi, li, pi as f64 - li)
+}
+
+/// Returns the logarithmic integral `li(x)`, the integral of `1 / ln t` from 0 to `x`.
+///
+/// `li(1)` is negative infinity, and `li(x)` is NaN for negative `x`.
+///
+/// Reference: [Ramanujan's series, Wikipedia][1]
+///
+/// [1]: https://en.wikipedia.org/wiki/Logarithmic_integral_function#Series_representation
+pub fn li(x: f64) -> f64 {
+    /// The Euler–Mascheroni constant.
+    const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+    if x == 0.0 {
+        return 0.0;
+    }
+    let ln = x.ln();
+    if ln == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+
+    // The terms are `(-1)^(n-1) ln^n / (n! 2^(n-1))`, weighted by the
+    // sums of `1 / (2k+1)` for `k <= (n-1)/2`.
+    let (mut sum, mut term, mut weight) = (0.0, ln, 0.0);
+    for n in 1..1000 {
+        if n & 1 == 1 {
+            weight += 1.0 / n as f64;
+        }
+        let delta = term * weight;
+        sum += delta;
+        if delta.abs() <= f64::EPSILON * sum.abs() {
+            break;
+        }
+        term *= -ln / (2 * (n + 1)) as f64;
+    }
+    EULER_GAMMA + ln.abs().ln() + x.sqrt() * sum
+}
+
+/// Counts the primes <= `n` in each window `[kw, (k+1)w)` of width `w`.
+///
+/// The range is sieved segment by segment in a single pass, and the last
+/// window, which contains `n`, may be cut short.
+///
+/// # Panics
+/// Panics if `w` is zero.
+pub fn prime_density_windows(n: usize, w: usize) -> Vec<usize> {
+    assert!(w != 0, "empty window");
+    let mut res = vec![0; n / w + 1];
+    for_each_segment(n, |seg, low| {
+        for p in seg.iter_values(low) {
+            res[p / w] += 1;
+        }
+    });
+    res
+}
+
+/// Counts the primes on the two diagonals of the `n × n` Ulam spiral,
+/// i.e. the integers `1..=n^2` arranged in a square spiral starting from 1.
+///
+/// With the `k`-th ring around 1 ending at `(2k + 1)^2`, the diagonals of an odd `n`
+/// hold its corners `4k^2 - 2k + 1`, `4k^2 + 1`, `4k^2 + 2k + 1` and `(2k + 1)^2`.
+/// An even `n` covers the corners `4k^2 - 2k + 1` up to its last ring and
+/// `4k^2 + 2k + 1` below it, along with the even `(2k - 1)^2 + 1` and `4k^2`.
+/// The corners are then looked up in a table of the primes <= `n^2`.
+///
+/// Reference: [Ulam spiral, Wikipedia](https://en.wikipedia.org/wiki/Ulam_spiral)
+///
+/// # Panics
+/// Panics if `n^2` overflows.
+pub fn prime_spiral_diagonals(n: usize) -> usize {
+    let max = n.checked_mul(n).expect("spiral too large");
+    if max < 2 {
+        return 0;
+    }
+    let table = Eratosthenes::gen_table(max);
+    let is_prime = |v: usize| unsafe { table.get(v) };
+
+    let (rings, odd) = (n / 2, !n.is_multiple_of(2));
+    let mut res = 0;
+    for k in 1..=rings {
+        let k_squared_4 = 4 * k * k;
+        res += is_prime(k_squared_4 - 2 * k + 1) as usize;
+        if odd || k < rings {
+            res += is_prime(k_squared_4 + 2 * k + 1) as usize;
+        }
+        if odd {
+            res += is_prime(k_squared_4 + 1) as usize;
+        }
+    }
+    // Of the even corners on the diagonals of an even `n`, only `(2 - 1)^2 + 1 = 2` is prime.
+    if !odd {
+        res += 1;
+    }
+    res
+}
+
+/// Returns an upper bound of the `n`-th prime.
+///
+/// Reference: [Rosser's theorem](https://en.wikipedia.org/wiki/Rosser%27s_theorem)
+#[inline]
+pub(crate) fn nth_prime_upper_bound(n: usize) -> usize {
+    if n < 6 {
+        return 11;
+    }
+    // p_n < n(ln n + ln ln n) for n >= 6.
+    let n = n as f64;
+    let ln = n.ln();
+    (n * (ln + ln.ln())) as usize + 1
+}
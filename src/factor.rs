@@ -0,0 +1,361 @@
+use super::{
+    primality::{is_prime_u64, mul_mod},
+    small::SMALL_PRIMES,
+};
+
+/// The primes used for trial division before Pollard's rho.
+const TRIAL_PRIMES: [u64; 11] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31];
+
+/// A table of the smallest prime factor of each integer up to a limit,
+/// generated by the linear sieve.
+pub struct SmallestPrimeFactorSieve {
+    /// The smallest prime factors, with `spf[0] = 0` and `spf[1] = 1`.
+    spf: Vec<u32>,
+}
+
+impl SmallestPrimeFactorSieve {
+    /// Sieves the smallest prime factors of `[0, limit]`.
+    ///
+    /// # Panics
+    /// Panics if `limit` is greater than `u32::MAX`.
+    pub fn new(limit: usize) -> Self {
+        assert!(limit <= u32::MAX as usize, "factors must fit in u32");
+        let mut spf = vec![0; limit + 1];
+        let mut primes = Vec::new();
+        if limit >= 1 {
+            spf[1] = 1;
+        }
+        for i in 2..=limit {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                primes.push(i as u32);
+            }
+            let spf_i = spf[i];
+            for &p in &primes {
+                let multiple = i * p as usize;
+                if p > spf_i || multiple > limit {
+                    break;
+                }
+                spf[multiple] = p;
+            }
+        }
+        Self { spf }
+    }
+
+    /// Returns the limit of the sieve.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.spf.len() - 1
+    }
+
+    /// Returns the table of smallest prime factors, indexed by the integers.
+    #[inline]
+    pub fn as_slice(&self) -> &[u32] {
+        &self.spf
+    }
+
+    /// Returns the smallest prime factor of `n`, or 1 if `n` is 1.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero or greater than the limit.
+    #[inline]
+    pub fn smallest_prime_factor(&self, n: usize) -> usize {
+        assert!(n != 0, "zero has no prime factors");
+        self.spf[n] as usize
+    }
+
+    /// Returns the largest prime factor of `n`, or 1 if `n` is 1.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero or greater than the limit.
+    pub fn largest_prime_factor(&self, mut n: usize) -> usize {
+        let mut res = 1;
+        while n > 1 {
+            let p = self.smallest_prime_factor(n);
+            res = p;
+            n /= p;
+        }
+        res
+    }
+}
+
+/// The prime factorization of a positive integer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Factorization {
+    /// The prime factors and their exponents, in ascending order of the primes.
+    factors: Vec<(u64, u32)>,
+}
+
+impl Factorization {
+    /// Factorizes `n`.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    pub fn new(n: u64) -> Self {
+        let mut primes = Vec::new();
+        for_each_prime_factor(n, |p| primes.push(p));
+        primes.sort_unstable();
+
+        let mut factors: Vec<(u64, u32)> = Vec::new();
+        for p in primes {
+            match factors.last_mut() {
+                Some((last, k)) if *last == p => *k += 1,
+                _ => factors.push((p, 1)),
+            }
+        }
+        Self { factors }
+    }
+
+    /// Returns the prime factors and their exponents, in ascending order of the primes.
+    #[inline]
+    pub fn factors(&self) -> &[(u64, u32)] {
+        &self.factors
+    }
+
+    /// Returns an iterator over the distinct prime factors in ascending order.
+    #[inline]
+    pub fn primes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.factors.iter().map(|&(p, _)| p)
+    }
+}
+
+/// An iterator over the prime factors of an integer and their exponents,
+/// in ascending order of the primes.
+///
+/// Factors are found lazily as the iterator advances: small ones by trial
+/// division with `SMALL_PRIMES`, and the rest by Pollard's rho. Nothing is allocated.
+pub struct FactorIter {
+    /// The part of the integer left to factorize.
+    n: u64,
+    /// The index of the next prime in `SMALL_PRIMES` to try.
+    cursor: usize,
+}
+
+impl FactorIter {
+    /// Creates an iterator over the prime factors of `n`.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    #[inline]
+    pub fn new(n: u64) -> Self {
+        assert!(n != 0, "zero has no prime factors");
+        Self { n, cursor: 0 }
+    }
+}
+
+impl Iterator for FactorIter {
+    type Item = (u64, u32);
+
+    fn next(&mut self) -> Option<(u64, u32)> {
+        let mut n = self.n;
+        if n == 1 {
+            return None;
+        }
+        let p = loop {
+            match SMALL_PRIMES.get(self.cursor) {
+                Some(&p) => {
+                    self.cursor += 1;
+                    let p = p as u64;
+                    if p * p > n {
+                        break n;
+                    }
+                    if n.is_multiple_of(p) {
+                        break p;
+                    }
+                }
+                None if is_prime_u64(n) => break n,
+                None => {
+                    let mut min = u64::MAX;
+                    for_each_prime_factor(n, |p| min = min.min(p));
+                    break min;
+                }
+            }
+        };
+
+        let mut k = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            k += 1;
+        }
+        self.n = n;
+        Some((p, k))
+    }
+}
+
+/// Counts the integers in `[1, n]` coprime to `m`.
+///
+/// The count is computed by inclusion-exclusion over the distinct prime factors of `m`.
+/// Every integer is coprime to 1, and only 1 is coprime to 0.
+pub fn coprime_count(n: usize, m: usize) -> usize {
+    if m == 0 {
+        return n.min(1);
+    }
+    let primes: Vec<u64> = Factorization::new(m as u64).primes().collect();
+
+    /// Sums `μ(d) floor(n / d)` over the squarefree `d` made of `primes` with `d <= n`.
+    fn sum(n: u64, d: u64, primes: &[u64], positive: bool) -> i128 {
+        let mut res = (n / d) as i128;
+        if !positive {
+            res = -res;
+        }
+        for (i, &p) in primes.iter().enumerate() {
+            match d.checked_mul(p) {
+                Some(d) if d <= n => res += sum(n, d, &primes[i + 1..], !positive),
+                // The primes are ascending, so no larger products fit either.
+                _ => break,
+            }
+        }
+        res
+    }
+    sum(n as u64, 1, &primes, true) as usize
+}
+
+/// Returns the largest prime factor of `n`, or 1 if `n` is 1.
+///
+/// Small factors are found by trial division, and the rest by Pollard's rho.
+/// No table is consulted: when a table of smallest prime factors is at hand,
+/// use `largest_prime_factor_via_spf`.
+///
+/// # Panics
+/// Panics if `n` is zero.
+pub fn largest_prime_factor(n: u64) -> u64 {
+    let mut res = 1;
+    for_each_prime_factor(n, |p| res = res.max(p));
+    res
+}
+
+/// Returns the largest prime factor of `n`, or 1 if `n` is 1, using a table
+/// of smallest prime factors such as `SmallestPrimeFactorSieve::as_slice`.
+///
+/// The factors are read from the table by repeated division. If `n` is out of
+/// the table, it falls back to `largest_prime_factor`.
+///
+/// # Panics
+/// Panics if `n` is zero.
+pub fn largest_prime_factor_via_spf(n: u64, spf: &[u32]) -> u64 {
+    assert!(n != 0, "zero has no prime factors");
+    if n >= spf.len() as u64 {
+        return largest_prime_factor(n);
+    }
+    let (mut n, mut res) = (n as usize, 1);
+    while n > 1 {
+        let p = spf[n] as usize;
+        res = p;
+        n /= p;
+    }
+    res as u64
+}
+
+/// Returns whether `a` and `b` are coprime, using a table of smallest prime factors
+/// such as `SmallestPrimeFactorSieve::as_slice`.
+///
+/// The prime factors of `a` are read from the table and tested against `b`.
+/// If either value is out of the table, it falls back to the binary GCD algorithm.
+pub fn coprime_via_spf(a: usize, b: usize, spf: &[u32]) -> bool {
+    if a == 0 || b == 0 || a >= spf.len() || b >= spf.len() {
+        return gcd(a as u64, b as u64) == 1;
+    }
+    let mut a = a;
+    while a > 1 {
+        let p = spf[a] as usize;
+        if b.is_multiple_of(p) {
+            return false;
+        }
+        while a.is_multiple_of(p) {
+            a /= p;
+        }
+    }
+    true
+}
+
+/// Calls `f` with each prime factor of `n`, with multiplicity and in no particular order.
+///
+/// # Panics
+/// Panics if `n` is zero.
+pub(crate) fn for_each_prime_factor(mut n: u64, mut f: impl FnMut(u64)) {
+    assert!(n != 0, "zero has no prime factors");
+    for &p in &TRIAL_PRIMES {
+        while n.is_multiple_of(p) {
+            f(p);
+            n /= p;
+        }
+    }
+    for_each_large_prime_factor(n, &mut f);
+}
+
+/// Calls `f` with each prime factor of `n`, which has no factors in `TRIAL_PRIMES`.
+fn for_each_large_prime_factor(n: u64, f: &mut impl FnMut(u64)) {
+    if n == 1 {
+        return;
+    }
+    if is_prime_u64(n) {
+        f(n);
+        return;
+    }
+    let d = pollard_rho(n);
+    for_each_large_prime_factor(d, f);
+    for_each_large_prime_factor(n / d, f);
+}
+
+/// Returns a nontrivial factor of an odd composite `n`, by Brent's variant of Pollard's rho.
+///
+/// Reference: [Pollard's rho algorithm, Wikipedia](https://en.wikipedia.org/wiki/Pollard%27s_rho_algorithm)
+fn pollard_rho(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+    for c in 1.. {
+        let next = |x: u64| ((x as u128 * x as u128 + c) % n as u128) as u64;
+        let (mut x, mut y, mut ys) = (0, 2, 2);
+        let (mut g, mut q, mut r) = (1, 1, 1);
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = next(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = next(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r <<= 1;
+        }
+        if g == n {
+            // The batch overshot, so backtrack one step at a time.
+            loop {
+                ys = next(ys);
+                g = gcd(x.abs_diff(ys), n);
+                if g != 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+/// Returns the greatest common divisor of `a` and `b`, by the binary GCD algorithm.
+#[inline]
+pub(crate) fn gcd(mut a: u64, mut b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    loop {
+        b >>= b.trailing_zeros();
+        if a > b {
+            (a, b) = (b, a);
+        }
+        b -= a;
+        if b == 0 {
+            return a << shift;
+        }
+    }
+}
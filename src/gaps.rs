@@ -0,0 +1,80 @@
+use super::{
+    counting::nth_prime_upper_bound,
+    sieve::{base_primes_up_to, for_each_segment, int_sqrt, Eratosthenes, Segments},
+};
+
+/// Returns the gap `p_{n+1} - p_n` following the `n`-th prime.
+///
+/// # Panics
+/// Panics if `n` is zero.
+pub fn nth_prime_gap(n: usize) -> usize {
+    assert!(n != 0, "primes are counted from 1");
+    let table = Eratosthenes::gen_table(nth_prime_upper_bound(n + 1));
+    let mut primes = table.iter_ones().skip(n - 1);
+    let p = primes.next().unwrap();
+    primes.next().unwrap() - p
+}
+
+/// Returns the gaps between consecutive primes below `limit`.
+pub fn prime_gaps(limit: usize) -> Vec<u32> {
+    if limit < 4 {
+        return Vec::new();
+    }
+    let table = Eratosthenes::gen_table(limit - 1);
+    let mut primes = table.iter_ones();
+    let mut prev = primes.next().unwrap();
+    primes
+        .map(|p| {
+            let gap = p - prev;
+            prev = p;
+            gap as u32
+        })
+        .collect()
+}
+
+/// Returns the mean gap between consecutive primes <= `n`, which is close to `n / π(n)`,
+/// or `None` if there are fewer than two such primes, i.e. `n < 3`.
+///
+/// The gaps telescope, so only the count and the last prime are tracked in a single pass.
+pub fn average_prime_gap(n: usize) -> Option<f64> {
+    let (mut count, mut last) = (0, 0);
+    for_each_segment(n, |seg, low| {
+        let ones = seg.count_ones();
+        if ones != 0 {
+            count += ones;
+            last = low + seg.len() - 1 - seg.leading_zeros_count();
+        }
+    });
+    if count < 2 {
+        return None;
+    }
+    Some((last - 2) as f64 / (count - 1) as f64)
+}
+
+/// Returns the first gap `p_{n+1} - p_n >= g` between consecutive primes below `limit`,
+/// as the gap and the prime `p_n` before it.
+///
+/// The range is sieved segment by segment, carrying the last prime of each
+/// segment over to the next, and the search stops at the first such gap.
+pub fn first_gap_at_least(g: usize, limit: usize) -> Option<(usize, usize)> {
+    if limit < 4 {
+        return None;
+    }
+    let high = limit - 1;
+    let seg_len = int_sqrt(high);
+    let primes = base_primes_up_to(seg_len);
+
+    let mut prev = None;
+    let mut segments = Segments::new(0, high, seg_len);
+    while let Some((seg, low)) = segments.next(&primes) {
+        for p in seg.iter_values(low) {
+            if let Some(prev) = prev {
+                if p - prev >= g {
+                    return Some((p - prev, prev));
+                }
+            }
+            prev = Some(p);
+        }
+    }
+    None
+}
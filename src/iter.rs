@@ -0,0 +1,330 @@
+use std::{
+    cmp::Reverse,
+    collections::{binary_heap::PeekMut, BinaryHeap, HashMap},
+};
+
+use super::{
+    bitset::{BitSet, BITS_PER_WORD},
+    factor::gcd,
+    primality::is_prime,
+    sieve::{base_primes_up_to, int_sqrt, Segments},
+    table::PrimeSieve,
+};
+
+/// The initial limit of the sieve of `PrimesInAP`.
+const AP_INITIAL_LIMIT: usize = 1 << 16;
+
+/// An iterator over the primes in `[low, high]`.
+///
+/// The base primes up to `sqrt(high)` are collected once, and the range
+/// is sieved lazily, one window at a time.
+pub struct PrimesInRange {
+    primes: Vec<u32>,
+    segments: Segments,
+    /// The primes in the current window.
+    buf: Vec<usize>,
+    pos: usize,
+}
+
+impl PrimesInRange {
+    /// Creates an iterator over the primes in `[low, high]`, which is empty if `low > high`.
+    pub fn new(low: usize, high: usize) -> Self {
+        let (limit, seg_len) = if low > high {
+            (0, 1)
+        } else {
+            let sqrt = int_sqrt(high);
+            (sqrt, sqrt.clamp(1, high - low + 1))
+        };
+        Self {
+            primes: base_primes_up_to(limit),
+            segments: Segments::new(low, high, seg_len),
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Restricts the iterator to the primes `≡ a (mod m)`.
+    ///
+    /// See `PrimesInResidue` for how this differs from `filter`.
+    ///
+    /// # Panics
+    /// Panics if `m` is zero.
+    pub fn filter_residue(self, a: usize, m: usize) -> PrimesInResidue {
+        assert!(m != 0, "zero modulus");
+        PrimesInResidue {
+            inner: self,
+            a: a % m,
+            m,
+        }
+    }
+}
+
+impl Iterator for PrimesInRange {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        while self.pos == self.buf.len() {
+            let (seg, low) = self.segments.next(&self.primes)?;
+            self.buf.clear();
+            self.buf.extend(seg.iter_values(low));
+            self.pos = 0;
+        }
+        let res = self.buf[self.pos];
+        self.pos += 1;
+        Some(res)
+    }
+}
+
+/// An iterator over the primes `≡ a (mod m)` in a range, created by `PrimesInRange::filter_residue`.
+///
+/// Each window is sieved in full as before, but only the positions in the class
+/// are decoded. For `m` shorter than a word, each word is masked with a shifted
+/// pattern of the positions before its ones are extracted; otherwise the `1/m`
+/// positions are read one by one. This saves the decoding of the other classes,
+/// which pays off when the class is sparse, e.g. for large `m`, but the sieving
+/// itself still dominates for small `m`.
+pub struct PrimesInResidue {
+    inner: PrimesInRange,
+    a: usize,
+    m: usize,
+}
+
+impl Iterator for PrimesInResidue {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        let inner = &mut self.inner;
+        while inner.pos == inner.buf.len() {
+            let (seg, low) = inner.segments.next(&inner.primes)?;
+            inner.buf.clear();
+            // The offset of the first `k >= low` with `k ≡ a`, without overflowing `a + m`.
+            let (a, r) = (self.a, low % self.m);
+            let start = if a >= r { a - r } else { a + (self.m - r) };
+            extend_strided(&mut inner.buf, seg, low, start, self.m);
+            inner.pos = 0;
+        }
+        let res = inner.buf[inner.pos];
+        inner.pos += 1;
+        Some(res)
+    }
+}
+
+/// Pushes the values of the ones at `start, start + step, ...` in a segment starting at `low`.
+fn extend_strided(buf: &mut Vec<usize>, seg: &BitSet, low: usize, start: usize, step: usize) {
+    if step >= BITS_PER_WORD {
+        buf.extend(
+            (start..seg.len())
+                .step_by(step)
+                .filter(|&i| unsafe { seg.get(i) })
+                .map(|i| low + i),
+        );
+        return;
+    }
+
+    // As in `BitSet::count_ones_strided`, with the position pattern of a word
+    // moving back by `BITS_PER_WORD % step` from one word to the next.
+    let pattern = (0..BITS_PER_WORD)
+        .step_by(step)
+        .fold(0usize, |pattern, i| pattern | 1 << i);
+    let shift_back = BITS_PER_WORD % step;
+    let mut first = start;
+    let mut base = low;
+    for &word in seg.words() {
+        let mut word = word & pattern << first;
+        while word != 0 {
+            buf.push(base + word.trailing_zeros() as usize);
+            word &= word - 1;
+        }
+        first = (first + step - shift_back) % step;
+        base += BITS_PER_WORD;
+    }
+}
+
+/// An unbounded iterator over the primes, by the incremental sieve of Eratosthenes.
+///
+/// Each odd prime `p` found so far is kept in a map keyed by its next odd multiple,
+/// so memory grows with the number of primes yielded rather than with a limit.
+/// When an upper bound is known, `PrimesInRange` is much faster.
+///
+/// Reference: [The Genuine Sieve of Eratosthenes, Melissa E. O'Neill][1]
+///
+/// [1]: https://www.cs.hmc.edu/~oneill/papers/Sieve-JFP.pdf
+#[derive(Default)]
+pub struct LazyPrimeIter {
+    /// The next composite to skip, mapped to the step between its successors.
+    composites: HashMap<usize, usize>,
+    /// The next candidate, or zero before 2 is yielded.
+    n: usize,
+}
+
+impl LazyPrimeIter {
+    /// Creates an iterator starting from 2.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Iterator for LazyPrimeIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.n == 0 {
+            self.n = 3;
+            return Some(2);
+        }
+        loop {
+            let n = self.n;
+            self.n = n.checked_add(2)?;
+            match self.composites.remove(&n) {
+                Some(step) => {
+                    // Move the prime to its next multiple not taken by another one.
+                    let mut next = n.checked_add(step);
+                    while let Some(m) = next.filter(|m| self.composites.contains_key(m)) {
+                        next = m.checked_add(step);
+                    }
+                    if let Some(next) = next {
+                        self.composites.insert(next, step);
+                    }
+                }
+                None => {
+                    // Multiples of `n` below `n^2` have smaller prime factors.
+                    if let Some(square) = n.checked_mul(n) {
+                        self.composites.insert(square, n << 1);
+                    }
+                    return Some(n);
+                }
+            }
+        }
+    }
+}
+
+/// An unbounded iterator over the primes, by O'Neill's priority queue sieve.
+///
+/// Like `LazyPrimeIter`, each odd prime found so far is kept with its next
+/// odd multiple, but in a min-heap instead of a map. It is mostly useful
+/// as a baseline for benchmarks against the bitset sieves.
+///
+/// Reference: [The Genuine Sieve of Eratosthenes, Melissa E. O'Neill][1]
+///
+/// [1]: https://www.cs.hmc.edu/~oneill/papers/Sieve-JFP.pdf
+#[derive(Default)]
+pub struct HeapSieve {
+    /// The next odd multiple of each prime, along with the step to the one after.
+    heap: BinaryHeap<Reverse<(usize, usize)>>,
+    /// The next candidate, or zero before 2 is yielded.
+    n: usize,
+}
+
+impl HeapSieve {
+    /// Creates an iterator starting from 2.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Iterator for HeapSieve {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.n == 0 {
+            self.n = 3;
+            return Some(2);
+        }
+        loop {
+            let n = self.n;
+            self.n = n.checked_add(2)?;
+
+            let mut is_composite = false;
+            while let Some(mut top) = self.heap.peek_mut() {
+                let Reverse((multiple, step)) = *top;
+                if multiple != n {
+                    break;
+                }
+                is_composite = true;
+                match multiple.checked_add(step) {
+                    Some(next) => *top = Reverse((next, step)),
+                    None => {
+                        PeekMut::pop(top);
+                    }
+                }
+            }
+            if !is_composite {
+                // Multiples of `n` below `n^2` have smaller prime factors.
+                if let Some(square) = n.checked_mul(n) {
+                    self.heap.push(Reverse((square, n << 1)));
+                }
+                return Some(n);
+            }
+        }
+    }
+}
+
+/// An iterator over the primes in the arithmetic progression `a, a + d, a + 2d, ...`.
+///
+/// The terms are looked up in a `PrimeSieve`, which is regenerated with twice
+/// the limit whenever a term goes beyond it, so memory grows with the last term
+/// and the terms are practically limited to around 10^10.
+///
+/// By Dirichlet's theorem, the progression holds infinitely many primes when
+/// `gcd(a, d) = 1`. Otherwise every term is divisible by the GCD, and only
+/// the GCD itself can be prime. The iterator ends if a term overflows `usize`.
+pub struct PrimesInAP {
+    a: usize,
+    d: usize,
+    sieve: PrimeSieve,
+    /// The next term to test, or `None` if the iterator is exhausted.
+    next: Option<usize>,
+}
+
+impl PrimesInAP {
+    /// Creates an iterator over the primes in the progression `a, a + d, a + 2d, ...`.
+    ///
+    /// # Panics
+    /// Panics if `d` is zero.
+    pub fn new(a: usize, d: usize) -> Self {
+        assert!(d != 0, "zero modulus");
+        Self {
+            a,
+            d,
+            sieve: PrimeSieve::new(AP_INITIAL_LIMIT.max(a)),
+            next: Some(a),
+        }
+    }
+}
+
+impl Iterator for PrimesInAP {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if gcd(self.a as u64, self.d as u64) != 1 {
+            self.next.take()?;
+            // The GCD is a term only if it is `a`, or `d` when `a` is zero.
+            let term = if self.a == 0 { self.d } else { self.a };
+            return Some(term).filter(|&term| is_prime(term));
+        }
+        loop {
+            let term = self.next?;
+            self.next = term.checked_add(self.d);
+            if term > self.sieve.limit() {
+                let limit = term.saturating_mul(2).min(usize::MAX - 1);
+                self.sieve = PrimeSieve::new(limit);
+            }
+            if self.sieve.is_prime(term) {
+                return Some(term);
+            }
+        }
+    }
+}
+
+/// Returns the first `count` primes in the arithmetic progression `a, a + d, a + 2d, ...`,
+/// or fewer if the progression runs out of primes, e.g. when `gcd(a, d) > 1`.
+///
+/// # Panics
+/// Panics if `d` is zero.
+pub fn primes_in_ap(a: usize, d: usize, count: usize) -> Vec<usize> {
+    PrimesInAP::new(a, d).take(count).collect()
+}
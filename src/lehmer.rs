@@ -0,0 +1,178 @@
+use super::{
+    bitset::{BitSet, BITS_PER_WORD},
+    sieve::{
+        base_primes_bytes, base_primes_up_to, int_sqrt, table_bytes, Eratosthenes,
+        SegmentedEratosthenes, Sieve,
+    },
+};
+
+/// The product of the first `WHEEL_PRIMES` primes.
+const WHEEL: usize = 2 * 3 * 5 * 7 * 11 * 13;
+const WHEEL_PRIMES: usize = 6;
+/// Euler's totient of `WHEEL`, i.e. `(2-1)(3-1)(5-1)(7-1)(11-1)(13-1)`.
+const WHEEL_TOTIENT: usize = 2 * 4 * 6 * 10 * 12;
+
+/// The maximum limit of the lookup table of `π`.
+const MAX_TABLE_LIMIT: usize = 1 << 28;
+/// The limit below which sieving is faster than Lehmer's formula.
+const SIEVE_THRESHOLD: usize = 1 << 16;
+
+/// The prime-counting method of Lehmer.
+///
+/// Only primes up to `n^{1/2}` are enumerated, and the count is
+/// derived from the Legendre function `φ(x, a)` by Lehmer's identity.
+/// Values of `π` up to `n^{2/3}` are looked up from a sieved table.
+///
+/// Reference: [Meissel–Lehmer algorithm, Wikipedia][1]
+///
+/// [1]: https://en.wikipedia.org/wiki/Meissel%E2%80%93Lehmer_algorithm
+pub struct LehmerPi;
+
+impl Sieve for LehmerPi {
+    fn name() -> &'static str {
+        "LehmerPi"
+    }
+
+    fn prime_pi(n: usize) -> usize {
+        if n < SIEVE_THRESHOLD {
+            return SegmentedEratosthenes::prime_pi(n);
+        }
+        Lehmer::new(n).pi(n)
+    }
+
+    fn memory_estimate(n: usize) -> usize {
+        if n < SIEVE_THRESHOLD {
+            return SegmentedEratosthenes::memory_estimate(n);
+        }
+        let table_len = table_limit(n) + 1;
+        base_primes_bytes(int_sqrt(n))
+            + table_bytes(table_len)
+            + table_len.div_ceil(BITS_PER_WORD) * 4
+            + WHEEL * 2
+    }
+}
+
+/// The context of Lehmer's formula for a fixed upper limit.
+struct Lehmer {
+    /// The primes up to the square root of the limit.
+    primes: Vec<u32>,
+    /// The prime table up to `table_limit`.
+    table: BitSet,
+    /// The number of primes before each word of the table.
+    table_counts: Vec<u32>,
+    table_limit: usize,
+    /// The numbers of integers coprime to `WHEEL` in `[1, r]` for each `r < WHEEL`.
+    wheel_counts: Vec<u16>,
+}
+
+impl Lehmer {
+    fn new(n: usize) -> Self {
+        let primes = base_primes_up_to(int_sqrt(n));
+
+        let table_limit = table_limit(n);
+        let table = Eratosthenes::gen_table(table_limit);
+        let mut count = 0;
+        let table_counts = table
+            .words()
+            .iter()
+            .map(|word| {
+                let res = count;
+                count += word.count_ones();
+                res
+            })
+            .collect();
+
+        let mut wheel_counts = Vec::with_capacity(WHEEL);
+        let mut count = 0;
+        for r in 0..WHEEL {
+            if primes[..WHEEL_PRIMES].iter().all(|&p| r % p as usize != 0) {
+                count += 1;
+            }
+            wheel_counts.push(count);
+        }
+
+        Self {
+            primes,
+            table,
+            table_counts,
+            table_limit,
+            wheel_counts,
+        }
+    }
+
+    /// Returns the `i`-th prime, counting from 1.
+    #[inline]
+    fn p(&self, i: usize) -> usize {
+        self.primes[i - 1] as usize
+    }
+
+    /// Counts the primes <= `x`.
+    fn pi(&self, x: usize) -> usize {
+        if x <= self.table_limit {
+            let word_i = x / BITS_PER_WORD;
+            let mask = !(!1 << (x % BITS_PER_WORD));
+            let word = self.table.words()[word_i] & mask;
+            return self.table_counts[word_i] as usize + word.count_ones() as usize;
+        }
+
+        let a = self.pi(int_sqrt(int_sqrt(x)));
+        let b = self.pi(int_sqrt(x));
+        let c = self.pi(int_cbrt(x));
+
+        let mut res = (self.phi(x, a) + (b + a - 2) * (b - a + 1) / 2) as isize;
+        for i in a + 1..=b {
+            let w = x / self.p(i);
+            res -= self.pi(w) as isize;
+            if i <= c {
+                let b_i = self.pi(int_sqrt(w));
+                for j in i..=b_i {
+                    res -= (self.pi(w / self.p(j)) - (j - 1)) as isize;
+                }
+            }
+        }
+        res as usize
+    }
+
+    /// Counts the integers in `[1, x]` not divisible by any of the first `a` primes.
+    fn phi(&self, x: usize, a: usize) -> usize {
+        if a == 0 {
+            return x;
+        }
+        if a == WHEEL_PRIMES {
+            return x / WHEEL * WHEEL_TOTIENT + self.wheel_counts[x % WHEEL] as usize;
+        }
+        if a < WHEEL_PRIMES {
+            return self.phi(x, a - 1) - self.phi(x / self.p(a), a - 1);
+        }
+
+        let p_a = self.p(a);
+        if x <= p_a {
+            return (x != 0) as usize;
+        }
+        // Only 1 and the primes in (p_a, x] are left.
+        if x <= self.table_limit && p_a * p_a > x {
+            return self.pi(x) - a + 1;
+        }
+        self.phi(x, a - 1) - self.phi(x / p_a, a - 1)
+    }
+}
+
+/// Returns the limit of the lookup table of `π` for `n`.
+#[inline]
+fn table_limit(n: usize) -> usize {
+    let cbrt = int_cbrt(n);
+    (cbrt * cbrt).min(MAX_TABLE_LIMIT).max(int_sqrt(n))
+}
+
+/// Returns the integer cube root of `n`.
+#[inline]
+fn int_cbrt(n: usize) -> usize {
+    let mut x = (n as f64).cbrt() as usize;
+    while x * x * x > n {
+        x -= 1;
+    }
+    while (x + 1).checked_pow(3).is_some_and(|cube| cube <= n) {
+        x += 1;
+    }
+    x
+}
@@ -1,2 +1,20 @@
+pub mod arith;
 pub mod bitset;
+pub mod collect;
+pub mod constellations;
+pub mod counting;
+pub mod factor;
+pub mod gaps;
+pub mod iter;
+pub mod lehmer;
+pub mod primality;
+pub mod safe;
 pub mod sieve;
+pub mod sink;
+pub mod small;
+pub mod stack;
+pub mod table;
+
+pub use collect::primes_between;
+pub use counting::{nth_prime, prime_pi, prime_pi_detailed};
+pub use primality::is_prime;
@@ -0,0 +1,470 @@
+use super::small::{is_small_prime, small_primes, SMALL_LIMIT, SMALL_PRIMES_MAX};
+
+/// The limit below which `is_prime` tests by trial division.
+const TRIAL_DIVISION_LIMIT: usize = 1_000_000;
+/// The largest prime representable in `u64`.
+const MAX_PRIME: u64 = u64::MAX - 58;
+
+/// The Miller-Rabin bases deterministic for all 64-bit integers.
+const MR_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Returns whether `n` is prime.
+///
+/// Small `n` are looked up from `SMALL_PRIMES`, and those up to `SMALL_PRIMES_MAX`
+/// from `small_primes`. Below 10^6, `n` is tested by trial division with `small_primes`,
+/// and above it by the Baillie-PSW test.
+pub fn is_prime(n: usize) -> bool {
+    if n <= SMALL_LIMIT {
+        return is_small_prime(n);
+    }
+    if n >= TRIAL_DIVISION_LIMIT {
+        return is_prime_bpsw(n as u64);
+    }
+    let primes = small_primes();
+    if n <= SMALL_PRIMES_MAX {
+        return primes.binary_search(&(n as u32)).is_ok();
+    }
+    primes
+        .iter()
+        .map(|&p| p as usize)
+        .take_while(|&p| p * p <= n)
+        .all(|p| !n.is_multiple_of(p))
+}
+
+/// Returns whether `n` is prime, by the deterministic Miller-Rabin test.
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &MR_BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    MR_BASES.iter().all(|&base| is_strong_pseudoprime(n, base))
+}
+
+/// Returns whether `n` is prime, by the Baillie-PSW test.
+///
+/// The test is known to be correct for all 64-bit integers.
+///
+/// Reference: [Baillie–PSW primality test, Wikipedia](https://en.wikipedia.org/wiki/Baillie%E2%80%93PSW_primality_test)
+pub fn is_prime_bpsw(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &MR_BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    is_strong_pseudoprime(n, 2) && is_strong_lucas_pseudoprime(n)
+}
+
+/// Returns the smallest prime greater than `n`.
+///
+/// # Panics
+/// Panics if the result does not fit in `u64`.
+pub fn next_prime(n: u64) -> u64 {
+    assert!(n < MAX_PRIME, "next prime out of range");
+    if n < SMALL_PRIMES_MAX as u64 {
+        let primes = small_primes();
+        return primes[primes.partition_point(|&p| p as u64 <= n)] as u64;
+    }
+    (n + 1..).find(|&m| is_prime_bpsw(m)).unwrap()
+}
+
+/// Returns the largest prime less than `n`, or `None` if `n <= 2`.
+pub fn prev_prime(n: u64) -> Option<u64> {
+    if n <= 2 {
+        return None;
+    }
+    if n <= SMALL_PRIMES_MAX as u64 {
+        let primes = small_primes();
+        return Some(primes[primes.partition_point(|&p| (p as u64) < n) - 1] as u64);
+    }
+    (2..n).rev().find(|&m| is_prime_bpsw(m))
+}
+
+/// Tests the first `count` terms of `a * b^k + c` for `k = 0, 1, ...` for primality.
+///
+/// The result is shorter than `count` if a term exceeds `u64::MAX`, as the
+/// sequence stops at the first such term. Negative terms are not prime.
+pub fn is_prime_sequence(a: u64, b: u64, c: i64, count: usize) -> Vec<bool> {
+    let mut res = Vec::with_capacity(count);
+    let mut power = Some(a);
+    while res.len() < count {
+        let ab = match power {
+            Some(ab) => ab,
+            None => break,
+        };
+        match ab.checked_add_signed(c) {
+            Some(term) => res.push(is_prime_u64(term)),
+            None if c < 0 => res.push(false),
+            None => break,
+        }
+        power = ab.checked_mul(b);
+    }
+    res
+}
+
+/// Returns `Some((p, k))` if `n = p^k` for a prime `p` and `k >= 1`.
+pub fn is_prime_power(n: u64) -> Option<(u64, u32)> {
+    (1..u64::BITS).find_map(|k| {
+        exact_root(n, k)
+            .filter(|&root| is_prime_u64(root))
+            .map(|root| (root, k))
+    })
+}
+
+/// Returns `Some((b, k))` if `n = b^k` for `b > 1` and `k >= 2`,
+/// preferring the smallest exponent.
+///
+/// See `is_prime_power` for prime bases.
+pub fn perfect_power(n: u64) -> Option<(u64, u32)> {
+    (2..u64::BITS).find_map(|k| exact_root(n, k).map(|root| (root, k)))
+}
+
+/// Returns whether the Mersenne number `2^p - 1` is prime, by the Lucas-Lehmer test.
+///
+/// The residues are reduced with `2^p ≡ 1` by shifts and masks. For `p < 64`
+/// they fit in a single `u64` squared as `u128`, and beyond that they are kept
+/// in 64-bit limbs, so `p` is not limited by the width of any integer type.
+///
+/// Reference: [Lucas–Lehmer primality test, Wikipedia](https://en.wikipedia.org/wiki/Lucas%E2%80%93Lehmer_primality_test)
+pub fn lucas_lehmer(p: u32) -> bool {
+    if p == 2 {
+        return true;
+    }
+    // `2^p - 1` is composite for composite `p`.
+    if !is_prime_u64(p as u64) {
+        return false;
+    }
+    if p < u64::BITS {
+        return lucas_lehmer_small(p);
+    }
+    let limbs = p.div_ceil(u64::BITS) as usize;
+    let mut s = vec![0; limbs];
+    s[0] = 4;
+    for _ in 0..p - 2 {
+        s = mersenne_reduce(square_limbs(&s), p);
+        // s - 2 mod 2^p - 1, where s <= 2^p - 1.
+        if s[0] < 2 && s[1..].iter().all(|&limb| limb == 0) {
+            let rem = 2 - s[0];
+            s = mersenne_limbs(p);
+            s[0] -= rem;
+        } else {
+            sub_limbs(&mut s, 2);
+        }
+    }
+    s.iter().all(|&limb| limb == 0) || s == mersenne_limbs(p)
+}
+
+/// The Lucas-Lehmer test for an odd prime `p < 64`, in a single word.
+fn lucas_lehmer_small(p: u32) -> bool {
+    let m = (1u64 << p) - 1;
+    let mut s = 4;
+    for _ in 0..p - 2 {
+        let sq = s as u128 * s as u128;
+        // sq < 2^2p, so two folds leave a value < 2^p + 2.
+        let sq = (sq & m as u128) + (sq >> p);
+        let mut sq = ((sq & m as u128) + (sq >> p)) as u64;
+        if sq >= m {
+            sq -= m;
+        }
+        s = if sq >= 2 { sq - 2 } else { sq + m - 2 };
+    }
+    s == 0
+}
+
+/// Returns the exponents `p <= limit` such that `2^p - 1` is prime, in ascending order.
+///
+/// Each prime exponent is tested with `lucas_lehmer`, which takes time
+/// roughly cubic in `p`, so this is only practical for limits in the thousands.
+pub fn mersenne_prime_exponents(limit: u32) -> Vec<u32> {
+    (2..=limit).filter(|&p| lucas_lehmer(p)).collect()
+}
+
+/// Returns whether `n` is both a Fibonacci number and prime.
+pub fn is_fibonacci_prime(n: u64) -> bool {
+    fibonacci_up_to(n).last() == Some(n) && is_prime_bpsw(n)
+}
+
+/// Returns the Fibonacci numbers <= `limit` that are prime, in ascending order.
+pub fn fibonacci_primes(limit: u64) -> Vec<u64> {
+    fibonacci_up_to(limit)
+        .filter(|&f| is_prime_bpsw(f))
+        .collect()
+}
+
+/// Returns an iterator over the Fibonacci numbers `2, 3, 5, 8, ...` <= `limit`,
+/// skipping the leading ones, which are not prime.
+///
+/// The numbers are generated as `u128`, so the one following `limit` never overflows.
+fn fibonacci_up_to(limit: u64) -> impl Iterator<Item = u64> {
+    std::iter::successors(Some((1u128, 2u128)), |&(a, b)| Some((b, a + b)))
+        .map(|(_, b)| b)
+        .take_while(move |&f| f <= limit as u128)
+        .map(|f| f as u64)
+}
+
+/// Returns the limbs of `2^p - 1`, in `ceil(p / 64)` limbs.
+fn mersenne_limbs(p: u32) -> Vec<u64> {
+    let limbs = p.div_ceil(u64::BITS) as usize;
+    let mut res = vec![u64::MAX; limbs];
+    if !p.is_multiple_of(u64::BITS) {
+        res[limbs - 1] = (1 << (p % u64::BITS)) - 1;
+    }
+    res
+}
+
+/// Returns `a^2` in twice as many limbs as `a`, by schoolbook multiplication.
+fn square_limbs(a: &[u64]) -> Vec<u64> {
+    let mut res = vec![0; a.len() * 2];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in a.iter().enumerate() {
+            let t = x as u128 * y as u128 + res[i + j] as u128 + carry;
+            res[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        res[i + a.len()] = carry as u64;
+    }
+    res
+}
+
+/// Reduces `x` to a value <= `2^p - 1` congruent modulo `2^p - 1`,
+/// in `ceil(p / 64)` limbs, by repeatedly adding the bits above `p` to those below.
+fn mersenne_reduce(mut x: Vec<u64>, p: u32) -> Vec<u64> {
+    let limbs = p.div_ceil(u64::BITS) as usize;
+    let (word_shift, bit_shift) = ((p / u64::BITS) as usize, p % u64::BITS);
+    loop {
+        let hi: Vec<u64> = (word_shift..x.len())
+            .map(|i| {
+                let lo = x[i] >> bit_shift;
+                match x.get(i + 1) {
+                    Some(&next) if bit_shift != 0 => lo | next << (u64::BITS - bit_shift),
+                    _ => lo,
+                }
+            })
+            .collect();
+        x.resize(limbs, 0);
+        if bit_shift != 0 {
+            x[limbs - 1] &= (1 << bit_shift) - 1;
+        }
+        if hi.iter().all(|&limb| limb == 0) {
+            return x;
+        }
+
+        // x = lo + hi, with room for a carry.
+        x.push(0);
+        let mut carry = false;
+        for (i, limb) in x.iter_mut().enumerate() {
+            let (sum, c1) = limb.overflowing_add(hi.get(i).copied().unwrap_or(0));
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+    }
+}
+
+/// Subtracts `b` from the limbs of `a`, which must be at least `b`.
+fn sub_limbs(a: &mut [u64], b: u64) {
+    let mut borrow = b;
+    for limb in a {
+        let (diff, overflow) = limb.overflowing_sub(borrow);
+        *limb = diff;
+        if !overflow {
+            return;
+        }
+        borrow = 1;
+    }
+}
+
+/// Returns the `k`-th root of `n` if it is an integer greater than 1.
+#[inline]
+fn exact_root(n: u64, k: u32) -> Option<u64> {
+    let root = int_root(n, k);
+    if root >= 2 && root.pow(k) == n {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+/// Returns the integer `k`-th root of `n`.
+#[inline]
+pub(crate) fn int_root(n: u64, k: u32) -> u64 {
+    match k {
+        1 => n,
+        2 => n.isqrt(),
+        _ => {
+            let mut x = (n as f64).powf(1.0 / k as f64) as u64;
+            while x.checked_pow(k).is_none_or(|pow| pow > n) {
+                x -= 1;
+            }
+            while (x + 1).checked_pow(k).is_some_and(|pow| pow <= n) {
+                x += 1;
+            }
+            x
+        }
+    }
+}
+
+/// Returns whether `n` is a strong probable prime to the given base,
+/// i.e. `n` passes a single round of the Miller-Rabin test.
+///
+/// Odd primes coprime to `base` always pass, so a composite `n` passing
+/// the test is a strong pseudoprime to `base`.
+///
+/// Reference: [Strong pseudoprime, Wikipedia](https://en.wikipedia.org/wiki/Strong_pseudoprime)
+pub fn is_strong_pseudoprime(n: u64, base: u64) -> bool {
+    if n < 3 || n & 1 == 0 {
+        return n == 2;
+    }
+    let base = base % n;
+    if base == 0 {
+        return true;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    let mut x = pow_mod(base, d, n);
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+    for _ in 1..s {
+        x = mul_mod(x, x, n);
+        if x == n - 1 {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns `a * b mod m`.
+#[inline]
+pub(crate) fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// Returns `base^exp mod m`.
+#[inline]
+pub(crate) fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut res = 1 % m;
+    base %= m;
+    while exp != 0 {
+        if exp & 1 != 0 {
+            res = mul_mod(res, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    res
+}
+
+/// Returns whether `n` is a strong Lucas probable prime with Selfridge's parameters.
+///
+/// `n` must be odd and not divisible by any prime below 40.
+fn is_strong_lucas_pseudoprime(n: u64) -> bool {
+    // No suitable `D` exists for perfect squares.
+    if n.isqrt().pow(2) == n {
+        return false;
+    }
+
+    // Find the first `D` in 5, -7, 9, -11, ... such that `(D/n) = -1`.
+    let mut d: i64 = 5;
+    let d_mod = loop {
+        let d_mod = i128::from(d).rem_euclid(i128::from(n)) as u64;
+        match jacobi(d_mod, n) {
+            -1 => break d_mod,
+            0 => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    };
+    // P = 1, Q = (1 - D) / 4.
+    let q_mod = i128::from((1 - d) / 4).rem_euclid(i128::from(n)) as u64;
+
+    let s = (n + 1).trailing_zeros();
+    let k = (n + 1) >> s;
+
+    let half = |x: u64| {
+        if x & 1 == 0 {
+            x >> 1
+        } else {
+            (x >> 1) + (n >> 1) + 1
+        }
+    };
+    let (mut u, mut v, mut q_k) = (1, 1, q_mod);
+    for i in (0..k.ilog2()).rev() {
+        // k -> 2k
+        u = mul_mod(u, v, n);
+        v = sub_mod(mul_mod(v, v, n), add_mod(q_k, q_k, n), n);
+        q_k = mul_mod(q_k, q_k, n);
+        if k >> i & 1 != 0 {
+            // k -> k + 1
+            let (u_prev, v_prev) = (u, v);
+            u = half(add_mod(u_prev, v_prev, n));
+            v = half(add_mod(mul_mod(d_mod, u_prev, n), v_prev, n));
+            q_k = mul_mod(q_k, q_mod, n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+    for _ in 1..s {
+        v = sub_mod(mul_mod(v, v, n), add_mod(q_k, q_k, n), n);
+        q_k = mul_mod(q_k, q_k, n);
+        if v == 0 {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns the Jacobi symbol `(a/n)` for an odd `n`.
+fn jacobi(mut a: u64, mut n: u64) -> i32 {
+    let mut res = 1;
+    a %= n;
+    while a != 0 {
+        let zeros = a.trailing_zeros();
+        a >>= zeros;
+        if zeros & 1 != 0 && (n & 7 == 3 || n & 7 == 5) {
+            res = -res;
+        }
+        if a & 3 == 3 && n & 3 == 3 {
+            res = -res;
+        }
+        let r = n % a;
+        n = a;
+        a = r;
+    }
+    if n == 1 {
+        res
+    } else {
+        0
+    }
+}
+
+/// Returns `a + b mod m` for `a, b < m`.
+#[inline]
+fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    let (sum, overflow) = a.overflowing_add(b);
+    if overflow || sum >= m {
+        sum.wrapping_sub(m)
+    } else {
+        sum
+    }
+}
+
+/// Returns `a - b mod m` for `a, b < m`.
+#[inline]
+fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a.wrapping_sub(b).wrapping_add(m)
+    }
+}
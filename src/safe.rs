@@ -0,0 +1,57 @@
+//! A curated entry point whose public surface is entirely safe.
+//!
+//! Everything re-exported here is checked: invalid inputs either panic or
+//! are reported through the return value, and no signature involves `unsafe`.
+//!
+//! `BitSet`, whose bit accessors are `unsafe` for speed, is deliberately left
+//! out along with `Eratosthenes`, whose tables are returned as `BitSet`s.
+//! `SegmentedEratosthenes::gen_full_table` is reachable through the re-exported
+//! type but is not part of this surface; its `BitSet` is only usable here
+//! through the safe methods such as `count_ones` and `iter_ones`.
+//! The crate still uses `unsafe` internally; this module only guarantees
+//! that code calling through it never has to.
+#![forbid(unsafe_code)]
+
+pub use super::{
+    arith::{
+        liouville_sieve, mertens, mobius_sieve, phi_sieve, totient_inverse, totient_sum_below,
+    },
+    collect::{prime_mask_bytes, primes_between, primes_column, primes_u64, PrimeColumn},
+    constellations::{
+        count_constellation, nth_twin_prime, prime_k_tuples, prime_tuples_count, safe_prime_count,
+        safe_primes_below,
+    },
+    counting::{
+        chebyshev_psi, chebyshev_theta, count_primes_congruent,
+        count_primes_in_arithmetic_progression, first_n_primes, li, nth_prime, pi_vs_li,
+        prime_density_windows, prime_pi, prime_pi_detailed, prime_pi_exclusive,
+        prime_pi_running_count, prime_power_sum, prime_product_mod, prime_spiral_diagonals,
+        prime_sum, segment_prime_sum,
+    },
+    factor::{
+        coprime_count, coprime_via_spf, largest_prime_factor, largest_prime_factor_via_spf,
+        FactorIter, Factorization, SmallestPrimeFactorSieve,
+    },
+    gaps::{average_prime_gap, first_gap_at_least, nth_prime_gap, prime_gaps},
+    iter::{primes_in_ap, HeapSieve, LazyPrimeIter, PrimesInAP, PrimesInRange, PrimesInResidue},
+    lehmer::LehmerPi,
+    primality::{
+        fibonacci_primes, is_fibonacci_prime, is_prime, is_prime_bpsw, is_prime_power,
+        is_prime_sequence, is_prime_u64, is_strong_pseudoprime, lucas_lehmer,
+        mersenne_prime_exponents, next_prime, perfect_power, prev_prime,
+    },
+    sieve::{
+        available_threads, prime_pi_no_alloc, sieve_memory_estimate, sieve_no_alloc,
+        InsufficientBasePrimes, OddSegmentedEratosthenes, ParallelSegmentedEratosthenes,
+        SegmentedEratosthenes, SegmentedSieveWorkspace, Sieve, SieveResult, SieveStats,
+    },
+    sink::{PrimeSink, RecordWidth, WriteSink},
+    small::{small_primes, SMALL_LIMIT, SMALL_PRIMES, SMALL_PRIMES_LEN, SMALL_PRIMES_MAX},
+    stack::{words_for, StackSieve},
+    table::{PrimeList, PrimeSieve},
+};
+
+#[cfg(feature = "flate2")]
+pub use super::sink::GzipPrimeSink;
+#[cfg(feature = "zstd")]
+pub use super::sink::ZstdPrimeSink;
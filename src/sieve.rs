@@ -1,103 +1,966 @@
-use super::bitset::BitSet;
+use std::{
+    any,
+    error::Error,
+    fmt, hint, mem,
+    num::NonZeroUsize,
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{
+    bitset::{BitSet, BITS_PER_WORD},
+    counting,
+    primality::next_prime,
+    sink::PrimeSink,
+    small::{small_primes, SMALL_PRIMES_MAX},
+};
 
 /// A trait for prime sieves.
 pub trait Sieve {
+    /// Returns the name of the algorithm, as reported in `SieveResult`.
+    ///
+    /// By default, this is the name of the implementing type without its module path,
+    /// as given by `std::any::type_name`, whose exact output is not guaranteed.
+    fn name() -> &'static str {
+        let name = any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
     fn prime_pi(n: usize) -> usize;
+
+    /// Counts the primes <= `n`, along with the limit, elapsed time and algorithm name.
+    fn prime_pi_detailed(n: usize) -> SieveResult {
+        let start = Instant::now();
+        let count = Self::prime_pi(n);
+        SieveResult {
+            count,
+            limit: n,
+            elapsed: start.elapsed(),
+            algorithm: Self::name(),
+        }
+    }
+
+    /// Returns an estimate of the peak number of bytes allocated by `prime_pi(n)`.
+    ///
+    /// The estimate is meant to reflect the order of magnitude, not to be exact.
+    /// By default, it is zero, i.e. unknown, so `prime_pi_checked` reserves nothing.
+    fn memory_estimate(_n: usize) -> usize {
+        0
+    }
+
+    /// Counts the primes <= `n` as `prime_pi`, returning `None` instead of
+    /// aborting when the memory it needs is known to be unavailable.
+    ///
+    /// By default, `memory_estimate(n)` bytes are reserved with `try_reserve_exact`
+    /// and released before counting. `None` is returned if:
+    /// - the estimate is `usize::MAX`, by which implementors report an unsupported `n`,
+    ///   e.g. `usize::MAX` for `Eratosthenes`, whose table would not be addressable;
+    /// - the estimate exceeds `isize::MAX` bytes;
+    /// - the allocator refuses to reserve the estimate.
+    ///
+    /// Since the estimate is not exact and the reservation is not kept,
+    /// an allocation failure while counting can still abort, notably where
+    /// memory is overcommitted.
+    ///
+    /// The probe is only as good as `memory_estimate`. An implementor keeping its
+    /// default of zero reserves nothing, so this never returns `None` and behaves
+    /// exactly as `prime_pi`. Every sieve in this crate overrides the estimate.
+    fn prime_pi_checked(n: usize) -> Option<usize> {
+        let mut probe: Vec<u8> = Vec::new();
+        probe.try_reserve_exact(Self::memory_estimate(n)).ok()?;
+        // Keep the unused reservation from being optimized out.
+        drop(hint::black_box(probe));
+        Some(Self::prime_pi(n))
+    }
+
+    /// Checks `prime_pi` against known values up to 10^6, as a smoke test.
+    fn verify() -> bool {
+        KNOWN_PRIME_PI
+            .iter()
+            .all(|&(n, pi)| Self::prime_pi(n) == pi)
+    }
+
+    /// Returns the sum of the primes <= `n`.
+    ///
+    /// By default, the primes are summed segment by segment with `for_each_segment`,
+    /// using memory proportional to `sqrt(n)`, since a count does not give their sum.
+    fn prime_sum(n: usize) -> u64 {
+        let mut res = 0;
+        for_each_segment(n, |seg, low| {
+            res += seg.iter_values(low).map(|p| p as u64).sum::<u64>();
+        });
+        res
+    }
+}
+
+/// Known values of `π(n)`, checked by `Sieve::verify`.
+const KNOWN_PRIME_PI: [(usize, usize); 11] = [
+    (0, 0),
+    (1, 0),
+    (2, 1),
+    (3, 2),
+    (10, 4),
+    (100, 25),
+    (1_000, 168),
+    (10_000, 1_229),
+    (65_536, 6_542),
+    (100_000, 9_592),
+    (1_000_000, 78_498),
+];
+
+/// The word in which the bits of the odd numbers are set.
+const ODD_PATTERN: usize = (usize::MAX / 3) << 1;
+
+/// The result of a prime count, as returned by `Sieve::prime_pi_detailed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SieveResult {
+    /// The number of primes <= `limit`.
+    pub count: usize,
+    /// The limit sieved up to.
+    pub limit: usize,
+    /// The time taken to count.
+    pub elapsed: Duration,
+    /// The name of the algorithm used.
+    pub algorithm: &'static str,
 }
 
 /// The sieve of Eratosthenes.
+///
+/// Since the whole table is allocated, `usize::MAX` is not supported as a limit.
 pub struct Eratosthenes;
 
 impl Eratosthenes {
+    /// Generates the prime table of `[0, n]`, in which the ones are the primes.
+    ///
+    /// # Panics
+    /// Panics if `n` is `usize::MAX`, since the table would not be addressable.
     pub fn gen_table(n: usize) -> BitSet {
+        let mut table = Self::gen_table_raw(n);
+        unsafe {
+            table.clear(0);
+            if n >= 1 {
+                table.clear(1);
+            }
+        }
+        table
+    }
+
+    /// Generates the prime table of `[0, n]` along with the number of primes in it.
+    ///
+    /// The count is kept while marking, by decrementing it for each newly cleared
+    /// bit, so the table is not traversed again as by `count_ones`.
+    ///
+    /// # Panics
+    /// Panics if `n` is `usize::MAX`, since the table would not be addressable.
+    pub fn gen_table_and_count(n: usize) -> (BitSet, usize) {
+        assert!(n != usize::MAX, "limit too large");
         let mut table = BitSet::new(n + 1, true);
         unsafe {
             table.clear(0);
-            table.clear(1);
+            if n >= 1 {
+                table.clear(1);
+            }
         }
+        let mut count = n.saturating_sub(1);
 
-        let (mut i, mut i_squared) = (2, 4);
+        for i in 2..=int_sqrt(n) {
+            let is_prime = unsafe { table.get(i) };
+            if is_prime {
+                let mut clear = |j| unsafe {
+                    if table.get(j) {
+                        table.clear(j);
+                        count -= 1;
+                    }
+                };
+                // Stepping only while `j <= n - i` keeps `j + i` from overflowing.
+                let mut j = i * i;
+                while j <= n - i {
+                    clear(j);
+                    j += i;
+                }
+                clear(j);
+            }
+        }
+        (table, count)
+    }
 
-        while i_squared <= n {
+    /// Returns the prime table of `[0, n]`, generated on the first call and cached globally.
+    ///
+    /// The cache holds a single table, which is only valid for the `n` of the first call.
+    ///
+    /// # Panics
+    /// Panics if `n` differs from the one of the first call.
+    pub fn get_or_gen_table(n: usize) -> &'static BitSet {
+        static TABLE: OnceLock<(usize, BitSet)> = OnceLock::new();
+        let (cached_n, table) = TABLE.get_or_init(|| (n, Self::gen_table(n)));
+        assert_eq!(*cached_n, n, "table cached for a different limit");
+        table
+    }
+
+    /// Generates the table of numbers in `[0, n]` surviving the sieve.
+    ///
+    /// Unlike `gen_table`, the bits of 0 and 1 are left set,
+    /// so callers must account for them.
+    ///
+    /// # Panics
+    /// Panics if `n` is `usize::MAX`, since the table would not be addressable.
+    pub fn gen_table_raw(n: usize) -> BitSet {
+        assert!(n != usize::MAX, "limit too large");
+        // Start from the odd numbers instead of clearing the multiples of 2.
+        let mut table = BitSet::with_pattern(n + 1, ODD_PATTERN);
+        unsafe {
+            table.set(0);
+            if n >= 2 {
+                table.set(2);
+            }
+        }
+
+        // Bounding `i` rather than `i^2` keeps the loop free of overflow
+        // for limits near `usize::MAX`, where `i^2` would wrap around.
+        for i in (3..=int_sqrt(n)).step_by(2) {
             let is_prime = unsafe { table.get(i) };
             if is_prime {
-                let mut j = i_squared;
-                loop {
+                // Only the odd multiples are left, and stepping only while
+                // `j <= n - 2i` keeps `j + 2i` from overflowing.
+                let (mut j, step) = (i * i, i << 1);
+                while j <= n - step {
                     unsafe { table.clear(j) }
-                    j += i;
-                    if j > n {
-                        break;
-                    }
+                    j += step;
                 }
+                unsafe { table.clear(j) }
             }
-            // (i+1)^2=i^2+2i+1
-            i_squared += (i << 1) | 1;
-            i += 1;
         }
         table
     }
 }
 
 impl Sieve for Eratosthenes {
+    fn name() -> &'static str {
+        "Eratosthenes"
+    }
+
     fn prime_pi(n: usize) -> usize {
         if n < 2 {
             return 0;
         }
         Self::gen_table(n).count_ones()
     }
+
+    fn memory_estimate(n: usize) -> usize {
+        if n < 2 {
+            return 0;
+        }
+        match n.checked_add(1) {
+            Some(len) => table_bytes(len),
+            // The table would not be addressable.
+            None => usize::MAX,
+        }
+    }
+
+    /// Returns the sum of the primes <= `n`, from the whole table as `counting::prime_sum`.
+    fn prime_sum(n: usize) -> u64 {
+        counting::prime_sum(n)
+    }
+}
+
+/// Returns the number of bytes used by the table of `Eratosthenes::gen_table(n)`,
+/// as `BitSet::memory_bytes`, without generating it.
+#[inline]
+pub fn sieve_memory_estimate(n: usize) -> usize {
+    mem::size_of::<BitSet>() + table_bytes(n.saturating_add(1))
+}
+
+/// Sieves the primes <= `n` into `buffer` without allocating, by the sieve of Eratosthenes.
+///
+/// The buffer is laid out like the words of a `BitSet`: bit `i % usize::BITS`
+/// of word `i / usize::BITS` is set if `i` is prime. The bits past `n` are cleared.
+///
+/// # Panics
+/// Panics if `buffer` is shorter than `n / usize::BITS + 1` words.
+pub fn sieve_no_alloc(buffer: &mut [usize], n: usize) {
+    let words = n / BITS_PER_WORD + 1;
+    assert!(words <= buffer.len(), "buffer too small");
+    let (buffer, rest) = buffer.split_at_mut(words);
+    rest.fill(0);
+    // Start from the odd numbers instead of clearing the multiples of 2.
+    buffer.fill(ODD_PATTERN);
+    buffer[words - 1] &= !(!1 << (n % BITS_PER_WORD));
+    buffer[0] &= !0b10;
+    if n >= 2 {
+        buffer[0] |= 0b100;
+    }
+
+    // Every index cleared is <= `n`, which is in bounds by the assertion above.
+    let clear = |buffer: &mut [usize], i: usize| unsafe {
+        *buffer.get_unchecked_mut(i / BITS_PER_WORD) &= !(1 << (i % BITS_PER_WORD));
+    };
+    for i in (3..=int_sqrt(n)).step_by(2) {
+        if buffer[i / BITS_PER_WORD] >> (i % BITS_PER_WORD) & 1 != 0 {
+            // Only the odd multiples are left, and stepping only while
+            // `j <= n - 2i` keeps `j + 2i` from overflowing.
+            let (mut j, step) = (i * i, i << 1);
+            while j <= n - step {
+                clear(buffer, j);
+                j += step;
+            }
+            clear(buffer, j);
+        }
+    }
+}
+
+/// Counts the primes <= `n` without allocating, using `buffer` as the table.
+///
+/// The buffer is left holding the table, as filled by `sieve_no_alloc`.
+///
+/// # Panics
+/// Panics if `buffer` is shorter than `n / usize::BITS + 1` words.
+pub fn prime_pi_no_alloc(buffer: &mut [usize], n: usize) -> usize {
+    sieve_no_alloc(buffer, n);
+    buffer.iter().map(|word| word.count_ones() as usize).sum()
 }
 
 /// The segmented sieve of Eratosthenes.
 ///
+/// All inputs up to `usize::MAX` are supported without overflow.
+///
 /// TODO: Find the threshold of segmentation.
 pub struct SegmentedEratosthenes;
 
 impl Sieve for SegmentedEratosthenes {
+    fn name() -> &'static str {
+        "SegmentedEratosthenes"
+    }
+
     fn prime_pi(n: usize) -> usize {
         if n < 2 {
             return 0;
         }
-        let mut res = 0;
-        let mut seg_len = int_sqrt(n);
+        let seg_len = int_sqrt(n);
+        let primes = base_primes_up_to(seg_len);
 
-        let mut seg = Eratosthenes::gen_table(seg_len);
-        let primes = collect_primes(&seg, seg_len);
-        res += primes.len();
+        primes.len() + count_range(&primes, seg_len + 1, n, seg_len)
+    }
+
+    fn memory_estimate(n: usize) -> usize {
+        if n < 2 {
+            return 0;
+        }
+        let seg_len = int_sqrt(n);
+        base_primes_bytes(seg_len) + table_bytes(seg_len)
+    }
+}
 
-        unsafe { seg.truncate(seg_len) }
-        seg.set_all();
+impl SegmentedEratosthenes {
+    /// Counts the primes <= `n`, using the given base primes instead of generating them.
+    ///
+    /// `base` must hold the primes in ascending order starting from 2, and
+    /// cover every prime up to `sqrt(n)`. Primes beyond `sqrt(n)` are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if `base` stops short of a prime <= `sqrt(n)`.
+    pub fn prime_pi_with_base(n: usize, base: &[u32]) -> Result<usize, InsufficientBasePrimes> {
+        if n < 2 {
+            return Ok(0);
+        }
+        let seg_len = int_sqrt(n);
+        // The base covers the integers below the prime following its last one.
+        let covered = match base.last() {
+            Some(&p) if p as usize >= seg_len => true,
+            Some(&p) => next_prime(p as u64) > seg_len as u64,
+            None => seg_len < 2,
+        };
+        if !covered {
+            return Err(InsufficientBasePrimes {
+                required: seg_len,
+                largest: base.last().copied(),
+            });
+        }
 
-        let (mut low, mut high) = (seg_len + 1, seg_len << 1);
-        loop {
-            let max = int_sqrt(high) as u32;
-            unsafe { for_each_max(&primes, max, |p| mark_non_primes(&mut seg, p, low, seg_len)) }
-            res += seg.count_ones();
+        let len = base.partition_point(|&p| p as usize <= seg_len);
+        let primes = with_dummy(&base[..len], seg_len);
+        Ok(len + count_range(&primes, seg_len + 1, n, seg_len))
+    }
 
-            low = high + 1;
-            if low > n {
-                break;
+    /// Emits the primes <= `n` to `sink` in ascending order, then finishes it.
+    ///
+    /// The primes are emitted segment by segment as they are sieved, so memory
+    /// stays proportional to `sqrt(n)` whatever the sink does with them.
+    pub fn stream_primes(n: usize, sink: &mut impl PrimeSink) {
+        for_each_segment(n, |seg, low| {
+            for p in seg.iter_values(low) {
+                sink.emit(p as u64);
             }
-            high = {
-                let next = high + seg_len;
-                if next > n {
-                    seg_len = n - low + 1;
-                    unsafe { seg.truncate(seg_len) }
-                    n
+        });
+        sink.finish();
+    }
+
+    /// Counts the primes <= `n` as `prime_pi`, collecting statistics of the segments.
+    ///
+    /// The segments cover `(sqrt(n), n]`, since the primes up to `sqrt(n)` are the base primes.
+    pub fn prime_pi_with_stats(n: usize) -> (usize, SieveStats) {
+        let mut stats = SieveStats::default();
+        if n < 2 {
+            return (0, stats);
+        }
+        let seg_len = int_sqrt(n);
+        let primes = base_primes_up_to(seg_len);
+        stats.seg_len = seg_len;
+
+        let mut res = primes.len();
+        let mut segments = Segments::new(seg_len + 1, n, seg_len);
+        loop {
+            let start = Instant::now();
+            let (count, low) = match segments.next(&primes) {
+                Some((seg, low)) => (seg.count_ones(), low),
+                None => break,
+            };
+            stats.time_per_segment.push(start.elapsed());
+
+            let high = low.saturating_add(seg_len - 1).min(n);
+            stats.marks += count_marks(&primes, low, high);
+            stats.primes_per_segment.push(count);
+            res += count;
+        }
+        (res, stats)
+    }
+
+    /// Generates the prime table of `[0, n]` as `Eratosthenes::gen_table`,
+    /// sieving it segment by segment.
+    ///
+    /// Segments are rounded up to whole words, so that each one is copied
+    /// word by word into its place in the table while still in cache.
+    ///
+    /// # Panics
+    /// Panics if `n` is `usize::MAX`, since the table would not be addressable.
+    pub fn gen_full_table(n: usize) -> BitSet {
+        assert!(n != usize::MAX, "limit too large");
+        let mut table = BitSet::new(n + 1, false);
+        if n < 2 {
+            return table;
+        }
+        let seg_len = int_sqrt(n).next_multiple_of(BITS_PER_WORD);
+        let primes = base_primes_up_to(int_sqrt(n));
+
+        let words = table.words_mut();
+        let mut segments = Segments::new(0, n, seg_len);
+        while let Some((seg, low)) = segments.next(&primes) {
+            let dst = &mut words[low / BITS_PER_WORD..];
+            let len = dst.len().min(seg.words().len());
+            dst[..len].copy_from_slice(&seg.words()[..len]);
+        }
+        table
+    }
+}
+
+/// Statistics of a run of the segmented sieve, collected by `prime_pi_with_stats`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SieveStats {
+    /// The length of each segment.
+    pub seg_len: usize,
+    /// The number of bits cleared one by one, excluding those copied from the wheel pattern.
+    pub marks: u64,
+    /// The number of primes found in each segment.
+    pub primes_per_segment: Vec<usize>,
+    /// The wall time taken to sieve and count each segment.
+    pub time_per_segment: Vec<Duration>,
+}
+
+impl SieveStats {
+    /// Returns the number of segments.
+    #[inline]
+    pub fn segments(&self) -> usize {
+        self.primes_per_segment.len()
+    }
+
+    /// Returns the total wall time taken by the segments.
+    #[inline]
+    pub fn total_time(&self) -> Duration {
+        self.time_per_segment.iter().sum()
+    }
+}
+
+impl fmt::Display for SieveStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segments = self.segments();
+        writeln!(f, "segments: {} of length {}", segments, self.seg_len)?;
+        writeln!(f, "marks: {}", self.marks)?;
+        if segments == 0 {
+            return Ok(());
+        }
+        let primes = &self.primes_per_segment;
+        writeln!(
+            f,
+            "primes per segment: min {}, mean {:.1}, max {}",
+            primes.iter().min().unwrap(),
+            primes.iter().sum::<usize>() as f64 / segments as f64,
+            primes.iter().max().unwrap(),
+        )?;
+        let times = &self.time_per_segment;
+        write!(
+            f,
+            "time per segment: min {:?}, mean {:?}, max {:?}, total {:?}",
+            times.iter().min().unwrap(),
+            self.total_time() / segments as u32,
+            times.iter().max().unwrap(),
+            self.total_time(),
+        )
+    }
+}
+
+/// The error returned when the base primes supplied to a segmented sieve
+/// do not reach the square root of the limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InsufficientBasePrimes {
+    /// The integer square root of the limit, which the base primes must cover.
+    pub required: usize,
+    /// The largest base prime supplied, if any.
+    pub largest: Option<u32>,
+}
+
+impl fmt::Display for InsufficientBasePrimes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.largest {
+            Some(p) => write!(f, "base primes up to {} do not cover {}", p, self.required),
+            None => write!(f, "no base primes to cover {}", self.required),
+        }
+    }
+}
+
+impl Error for InsufficientBasePrimes {}
+
+/// The segmented sieve of Eratosthenes, storing only the odd numbers in each segment.
+///
+/// Bit `i` of a segment starting at the odd number `low` stands for `low + 2i`,
+/// so a segment of the same length as in `SegmentedEratosthenes` covers twice
+/// the range, halving the number of segments and the bits per integer.
+/// The multiples of 3, 5 and 7 are copied from a pattern, as the wheel is there.
+///
+/// All inputs up to `usize::MAX` are supported without overflow.
+pub struct OddSegmentedEratosthenes;
+
+impl Sieve for OddSegmentedEratosthenes {
+    fn name() -> &'static str {
+        "OddSegmentedEratosthenes"
+    }
+
+    fn prime_pi(n: usize) -> usize {
+        if n < 2 {
+            return 0;
+        }
+        let seg_len = int_sqrt(n);
+        let primes = base_primes_up_to(seg_len);
+        let mut seg = BitSet::new(seg_len, true);
+        // The largest odd number <= `n`.
+        let last = n - (1 - n % 2);
+
+        // Count 2 up front, and the odd primes segment by segment.
+        let mut res = 1;
+        let mut low: usize = 1;
+        loop {
+            let high = match low.checked_add(2 * (seg_len - 1)) {
+                Some(high) if high < last => high,
+                _ => last,
+            };
+            let len = odd_index(high, low) + 1;
+
+            let pattern = odd_wheel_pattern();
+            let shift = odd_index(low, 1) % ODD_WHEEL;
+            seg.fill_words(|i| {
+                let bit_i = shift + i % ODD_WHEEL_PERIOD_WORDS * BITS_PER_WORD;
+                let (word_i, bit_i) = (bit_i / BITS_PER_WORD, bit_i % BITS_PER_WORD);
+                if bit_i == 0 {
+                    pattern[word_i]
                 } else {
-                    next
+                    pattern[word_i] >> bit_i | pattern[word_i + 1] << (BITS_PER_WORD - bit_i)
                 }
-            };
-            seg.set_all();
+            });
+            for p in ODD_WHEEL_PRIMES {
+                if (low..=high).contains(&p) {
+                    unsafe { seg.set(odd_index(p, low)) }
+                }
+            }
+
+            let max = int_sqrt(high).min(u32::MAX as usize - 1) as u32;
+            // Skip 2 along with the primes in the pattern.
+            if let Some(primes) = primes.get(ODD_WHEEL_PRIMES.len() + 1..) {
+                unsafe { for_each_max(primes, max, |p| mark_odd_non_primes(&mut seg, p, low, len)) }
+            }
+
+            for i in len..seg_len {
+                unsafe { seg.clear(i) }
+            }
+            if low == 1 {
+                unsafe { seg.clear(0) }
+            }
+            res += seg.count_ones();
+
+            if high == last {
+                return res;
+            }
+            low = high + 2;
+        }
+    }
+
+    fn memory_estimate(n: usize) -> usize {
+        if n < 2 {
+            return 0;
+        }
+        let seg_len = int_sqrt(n);
+        base_primes_bytes(seg_len) + table_bytes(seg_len)
+    }
+}
+
+/// The segmented sieve of Eratosthenes, sieving on multiple threads.
+pub struct ParallelSegmentedEratosthenes;
+
+impl ParallelSegmentedEratosthenes {
+    /// Counts the primes <= `n`, splitting the work among `threads` threads.
+    ///
+    /// Zero threads is treated as one.
+    pub fn prime_pi_with_threads(n: usize, threads: usize) -> usize {
+        if n < 2 {
+            return 0;
+        }
+        let seg_len = int_sqrt(n);
+        let primes = base_primes_up_to(seg_len);
+
+        let (low, len) = (seg_len + 1, n - seg_len);
+        let threads = Self::threads_used(n, threads);
+        if threads == 1 {
+            return primes.len() + count_range(&primes, low, n, seg_len);
+        }
+
+        let chunk_len = (len - 1) / threads + 1;
+        let primes = &primes[..];
+        let res: usize = thread::scope(|s| {
+            let handles: Vec<_> = (0..threads)
+                .map(|i| {
+                    let chunk_low = low.saturating_add(i * chunk_len);
+                    let chunk_high = chunk_low.saturating_add(chunk_len - 1).min(n);
+                    s.spawn(move || count_range(primes, chunk_low, chunk_high, seg_len))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+        primes.len() + res
+    }
+
+    /// Returns the number of threads actually spawned for `n` when `threads` are requested.
+    #[inline]
+    fn threads_used(n: usize, threads: usize) -> usize {
+        let seg_len = int_sqrt(n);
+        // Every thread should sieve at least one full segment.
+        threads.max(1).min(((n - seg_len) / seg_len).max(1))
+    }
+}
+
+impl Sieve for ParallelSegmentedEratosthenes {
+    fn name() -> &'static str {
+        "ParallelSegmentedEratosthenes"
+    }
+
+    fn prime_pi(n: usize) -> usize {
+        Self::prime_pi_with_threads(n, available_threads())
+    }
+
+    fn memory_estimate(n: usize) -> usize {
+        if n < 2 {
+            return 0;
+        }
+        let seg_len = int_sqrt(n);
+        base_primes_bytes(seg_len)
+            + Self::threads_used(n, available_threads()) * table_bytes(seg_len)
+    }
+}
+
+/// Returns the number of threads available to the program.
+///
+/// Falls back to one thread when it cannot be determined,
+/// which happens on some sandboxed platforms.
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Reusable buffers for repeated segmented sieving.
+///
+/// The buffers grow as needed but never shrink, amortizing allocation across calls.
+/// A workspace is not meant to be shared: use one per thread.
+#[derive(Default)]
+pub struct SegmentedSieveWorkspace {
+    /// The base primes, collected by `base_primes_up_to`.
+    primes: Vec<u32>,
+    primes_limit: usize,
+    /// The segment buffer and its length.
+    seg: Option<(BitSet, usize)>,
+}
+
+impl SegmentedSieveWorkspace {
+    /// Creates a new empty workspace.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the primes <= `n`, reusing the buffers of the workspace.
+    pub fn prime_pi(&mut self, n: usize) -> usize {
+        if n < 2 {
+            return 0;
         }
+        let sqrt = int_sqrt(n);
+        if self.primes_limit < sqrt {
+            self.primes = base_primes_up_to(sqrt);
+            self.primes_limit = sqrt;
+        }
+
+        let (seg, seg_len) = match self.seg.take() {
+            Some((seg, seg_len)) if seg_len >= sqrt => (seg, seg_len),
+            _ => (BitSet::new(sqrt, true), sqrt),
+        };
+
+        let mut res = self.primes.partition_point(|&p| p as usize <= sqrt);
+        let mut segments = Segments::with_buffer(seg, sqrt + 1, n, seg_len);
+        while let Some((seg, _)) = segments.next(&self.primes) {
+            res += seg.count_ones();
+        }
+        self.seg = Some((segments.into_buffer(), seg_len));
         res
     }
 }
 
+/// Sieves `[0, n]` segment by segment, calling `f` with each segment and its `low` offset.
+///
+/// Bit `i` of a segment is set if `low + i` is prime, so several statistics
+/// can be gathered in a single pass. The segments have length `sqrt(n)`, and
+/// the bits past `n` in the last one are cleared.
+///
+/// A single buffer is reused for all segments, so each one is only valid
+/// during its call and is overwritten by the next.
+#[inline]
+pub fn for_each_segment(n: usize, mut f: impl FnMut(&BitSet, usize)) {
+    if n < 2 {
+        return;
+    }
+    let seg_len = int_sqrt(n);
+    let primes = base_primes_up_to(seg_len);
+
+    let mut segments = Segments::new(0, n, seg_len);
+    while let Some((seg, low)) = segments.next(&primes) {
+        f(seg, low);
+    }
+}
+
+/// Counts the primes in `[low, high]` with segments of length `seg_len`.
+#[inline]
+fn count_range(primes: &[u32], low: usize, high: usize, seg_len: usize) -> usize {
+    let mut res = 0;
+    let mut segments = Segments::new(low, high, seg_len);
+    while let Some((seg, _)) = segments.next(primes) {
+        res += seg.count_ones();
+    }
+    res
+}
+
+/// A segmented sieve over `[low, high]`, sieving one segment at a time.
+///
+/// The base primes passed to `next` must be collected by `base_primes_up_to`
+/// with a limit no less than the integer square root of `high`.
+pub(crate) struct Segments {
+    seg: BitSet,
+    seg_len: usize,
+    low: usize,
+    high: usize,
+    done: bool,
+}
+
+impl Segments {
+    /// Creates a new `Segments` over `[low, high]` with the given segment length.
+    #[inline]
+    pub(crate) fn new(low: usize, high: usize, seg_len: usize) -> Self {
+        Self::with_buffer(BitSet::new(seg_len, true), low, high, seg_len)
+    }
+
+    /// Creates a new `Segments` reusing the given buffer of length `seg_len`.
+    #[inline]
+    pub(crate) fn with_buffer(seg: BitSet, low: usize, high: usize, seg_len: usize) -> Self {
+        Self {
+            seg,
+            seg_len,
+            low,
+            high,
+            done: low > high,
+        }
+    }
+
+    /// Returns the buffer for reuse.
+    #[inline]
+    pub(crate) fn into_buffer(self) -> BitSet {
+        self.seg
+    }
+
+    /// Sieves the next segment, returning it along with its `low` offset.
+    ///
+    /// The bits past the end of the last segment are cleared.
+    #[inline]
+    pub(crate) fn next(&mut self, primes: &[u32]) -> Option<(&BitSet, usize)> {
+        if self.done {
+            return None;
+        }
+        let low = self.low;
+        let high = match low.checked_add(self.seg_len - 1) {
+            Some(high) if high < self.high => high,
+            _ => self.high,
+        };
+        let len = high - low + 1;
+
+        let seg = &mut self.seg;
+        // Copy the multiples of the wheel primes from the pattern, shifted to `low`.
+        let pattern = wheel_pattern();
+        let shift = low % WHEEL;
+        seg.fill_words(|i| {
+            let bit_i = shift + i % WHEEL_PERIOD_WORDS * BITS_PER_WORD;
+            let (word_i, bit_i) = (bit_i / BITS_PER_WORD, bit_i % BITS_PER_WORD);
+            if bit_i == 0 {
+                pattern[word_i]
+            } else {
+                pattern[word_i] >> bit_i | pattern[word_i + 1] << (BITS_PER_WORD - bit_i)
+            }
+        });
+        for p in WHEEL_PRIMES {
+            if (low..=high).contains(&p) {
+                unsafe { seg.set(p - low) }
+            }
+        }
+
+        // `u32::MAX` is not a prime, and must stay below the dummy element.
+        let max = int_sqrt(high).min(u32::MAX as usize - 1) as u32;
+        // The dummy element is kept after the rest of the primes.
+        if let Some(primes) = primes.get(WHEEL_PRIMES.len()..) {
+            unsafe { for_each_max(primes, max, |p| mark_non_primes(seg, p, low, len)) }
+        }
+
+        for i in len..self.seg_len {
+            unsafe { seg.clear(i) }
+        }
+        for i in low..=high.min(1) {
+            unsafe { seg.clear(i - low) }
+        }
+
+        if high == self.high {
+            self.done = true;
+        } else {
+            self.low = high + 1;
+        }
+        Some((seg, low))
+    }
+}
+
+/// The primes whose multiples are copied from the wheel pattern.
+const WHEEL_PRIMES: [usize; 4] = [2, 3, 5, 7];
+/// The product of `WHEEL_PRIMES`, i.e. the period of the pattern.
+const WHEEL: usize = 2 * 3 * 5 * 7;
+/// The number of words after which the pattern repeats word-aligned,
+/// i.e. `lcm(WHEEL, BITS_PER_WORD) / BITS_PER_WORD`.
+const WHEEL_PERIOD_WORDS: usize = WHEEL / 2;
+
+/// Returns the pattern of integers coprime to `WHEEL`, in which the ones
+/// are the integers not divisible by any of `WHEEL_PRIMES`.
+///
+/// It covers one word-aligned period shifted by up to `WHEEL` bits,
+/// plus one word to read across word boundaries.
+fn wheel_pattern() -> &'static [usize] {
+    static PATTERN: OnceLock<Vec<usize>> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        let words = WHEEL_PERIOD_WORDS + WHEEL / BITS_PER_WORD + 2;
+        (0..words)
+            .map(|word_i| {
+                (0..BITS_PER_WORD)
+                    .filter(|bit_i| {
+                        let v = word_i * BITS_PER_WORD + bit_i;
+                        WHEEL_PRIMES.iter().all(|&p| !v.is_multiple_of(p))
+                    })
+                    .fold(0, |word, bit_i| word | 1 << bit_i)
+            })
+            .collect()
+    })
+}
+
+/// The odd primes whose multiples are copied from the odd wheel pattern.
+const ODD_WHEEL_PRIMES: [usize; 3] = [3, 5, 7];
+/// The period of the odd wheel pattern in odd numbers, i.e. `3 * 5 * 7`.
+const ODD_WHEEL: usize = 3 * 5 * 7;
+/// The number of words after which the odd pattern repeats word-aligned,
+/// i.e. `lcm(ODD_WHEEL, BITS_PER_WORD) / BITS_PER_WORD`.
+const ODD_WHEEL_PERIOD_WORDS: usize = ODD_WHEEL;
+
+/// Returns the index of the odd number `v` in a segment starting at the odd number `low`.
+#[inline]
+fn odd_index(v: usize, low: usize) -> usize {
+    (v - low) / 2
+}
+
+/// Returns the odd pattern, in which bit `i` is set if `2i + 1`
+/// is not divisible by any of `ODD_WHEEL_PRIMES`.
+///
+/// Like `wheel_pattern`, it covers one word-aligned period shifted by up to
+/// `ODD_WHEEL` bits, plus one word to read across word boundaries.
+fn odd_wheel_pattern() -> &'static [usize] {
+    static PATTERN: OnceLock<Vec<usize>> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        let words = ODD_WHEEL_PERIOD_WORDS + ODD_WHEEL / BITS_PER_WORD + 2;
+        (0..words)
+            .map(|word_i| {
+                (0..BITS_PER_WORD)
+                    .filter(|bit_i| {
+                        let v = 2 * (word_i * BITS_PER_WORD + bit_i) + 1;
+                        ODD_WHEEL_PRIMES.iter().all(|&p| !v.is_multiple_of(p))
+                    })
+                    .fold(0, |word, bit_i| word | 1 << bit_i)
+            })
+            .collect()
+    })
+}
+
+/// Returns the primes <= `limit` followed by a dummy element, as `collect_primes`.
+///
+/// Limits up to `SMALL_PRIMES_MAX` are copied from `small_primes` without sieving.
+#[inline]
+pub(crate) fn base_primes_up_to(limit: usize) -> Vec<u32> {
+    if limit <= SMALL_PRIMES_MAX {
+        let primes = small_primes();
+        let len = primes.partition_point(|&p| p as usize <= limit);
+        return with_dummy(&primes[..len], limit);
+    }
+    collect_primes(&Eratosthenes::gen_table(limit), limit)
+}
+
+/// Returns the number of bytes of a `BitSet` of length `len`.
+#[inline]
+pub(crate) fn table_bytes(len: usize) -> usize {
+    len.div_ceil(BITS_PER_WORD) * (BITS_PER_WORD / 8)
+}
+
+/// Returns an estimate of the number of bytes allocated by `base_primes_up_to(limit)`,
+/// including the table it is collected from.
+///
+/// Reference: [Rosser and Schoenfeld's bound](https://en.wikipedia.org/wiki/Prime-counting_function#Inequalities)
+#[inline]
+pub(crate) fn base_primes_bytes(limit: usize) -> usize {
+    // π(x) < 1.25506 x / ln x for x > 1.
+    let primes = if limit < 2 {
+        0
+    } else {
+        let limit = limit as f64;
+        (1.25506 * limit / limit.ln()) as usize
+    };
+    table_bytes(limit.saturating_add(1)) + (primes + 1) * 4
+}
+
 /// Returns the integer square root of `n`.
 ///
 /// Reference: [Wikipedia](https://en.wikipedia.org/wiki/Integer_square_root)
 #[inline]
-fn int_sqrt(n: usize) -> usize {
+pub(crate) fn int_sqrt(n: usize) -> usize {
     let mut x0 = n >> 1;
     if x0 != 0 {
         let mut x1 = (x0 + n / x0) >> 1;
@@ -115,19 +978,42 @@ fn int_sqrt(n: usize) -> usize {
 ///
 /// A dummy element is added at the end of the result (which is
 /// not safely accessible), in order to avoid bounds check.
+/// It is greater than any prime <= `seg_len`, saturating at `u32::MAX`.
+///
+/// At most `count_ones` elements are written before the dummy one, so a
+/// disagreement between `count_ones` and `iter_ones` can never write out
+/// of bounds, and is caught by a debug assertion.
 #[inline]
 fn collect_primes(seg: &BitSet, seg_len: usize) -> Vec<u32> {
     let len = seg.count_ones();
     let mut res = Vec::with_capacity(len + 1);
+    let mut ones = seg.iter_ones();
+    let mut written = 0;
     unsafe {
         let mut ptr = res.as_mut_ptr();
-        for p in seg.iter_ones() {
+        for p in ones.by_ref().take(len) {
             *ptr = p as u32;
             ptr = ptr.add(1);
+            written += 1;
         }
-        *ptr = (seg_len + 1) as u32;
-        res.set_len(len);
+        *ptr = (seg_len + 1).min(u32::MAX as usize) as u32;
+        res.set_len(written);
     }
+    debug_assert!(
+        written == len && ones.next().is_none(),
+        "count_ones disagrees with iter_ones"
+    );
+    res
+}
+
+/// Copies the primes <= `seg_len` with a dummy element added at the end,
+/// as in the result of `collect_primes`.
+#[inline]
+fn with_dummy(primes: &[u32], seg_len: usize) -> Vec<u32> {
+    let mut res = Vec::with_capacity(primes.len() + 1);
+    res.extend_from_slice(primes);
+    res.push((seg_len + 1).min(u32::MAX as usize) as u32);
+    unsafe { res.set_len(primes.len()) }
     res
 }
 
@@ -145,16 +1031,65 @@ unsafe fn for_each_max(primes: &[u32], max: u32, mut f: impl FnMut(u32)) {
     }
 }
 
-/// Marks multiples of `p` as non-primes in a segment.
+/// Counts the bits `Segments::next` clears one by one for the segment `[low, high]`,
+/// i.e. the multiples of the base primes beyond the wheel from their squares.
+fn count_marks(primes: &[u32], low: usize, high: usize) -> u64 {
+    let max = int_sqrt(high);
+    primes
+        .iter()
+        .skip(WHEEL_PRIMES.len())
+        .map(|&p| p as usize)
+        .take_while(|&p| p <= max)
+        .map(|p| {
+            let first = match low.div_ceil(p).checked_mul(p) {
+                Some(first) => first.max(p * p),
+                None => return 0,
+            };
+            if first > high {
+                0
+            } else {
+                ((high - first) / p + 1) as u64
+            }
+        })
+        .sum()
+}
+
+/// Marks odd multiples of an odd `p` as non-primes in a segment of odd numbers
+/// starting at `low`, starting from `p^2`.
 #[inline]
-fn mark_non_primes(seg: &mut BitSet, p: u32, low: usize, seg_len: usize) {
+fn mark_odd_non_primes(seg: &mut BitSet, p: u32, low: usize, seg_len: usize) {
     let p = p as usize;
-    let mut p_mul = low / p * p;
-    if p_mul < low {
-        p_mul += p;
+    let p_squared = p * p;
+    let mut i = if low <= p_squared {
+        odd_index(p_squared, low)
+    } else {
+        // `low + offset` is the first multiple of `p`, which must be odd.
+        let offset = (p - low % p) % p;
+        if offset.is_multiple_of(2) {
+            offset / 2
+        } else {
+            (offset + p) / 2
+        }
+    };
+
+    // Consecutive odd multiples are `2p` apart, i.e. `p` bits.
+    while i < seg_len {
+        unsafe { seg.clear(i) }
+        i += p;
     }
+}
+
+/// Marks multiples of `p` as non-primes in a segment, starting from `p^2`.
+#[inline]
+fn mark_non_primes(seg: &mut BitSet, p: u32, low: usize, seg_len: usize) {
+    let p = p as usize;
+    let p_squared = p * p;
+    let mut i = if low <= p_squared {
+        p_squared - low
+    } else {
+        (p - low % p) % p
+    };
 
-    let mut i = p_mul - low;
     while i < seg_len {
         unsafe { seg.clear(i) }
         i += p;
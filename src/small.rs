@@ -0,0 +1,45 @@
+use std::sync::OnceLock;
+
+use super::sieve::Eratosthenes;
+
+/// The largest integer covered by `SMALL_PRIMES`.
+pub const SMALL_LIMIT: usize = 256;
+
+/// The primes <= `SMALL_LIMIT` in ascending order.
+pub const SMALL_PRIMES: [u32; 54] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251,
+];
+
+/// The number of primes in `small_primes`.
+pub const SMALL_PRIMES_LEN: usize = 10_000;
+/// The largest prime in `small_primes`, i.e. the 10000-th prime.
+pub const SMALL_PRIMES_MAX: usize = 104_729;
+
+/// Returns the first `SMALL_PRIMES_LEN` primes in ascending order.
+///
+/// The primes are sieved on the first call and shared by all threads afterwards.
+pub fn small_primes() -> &'static [u32] {
+    static PRIMES: OnceLock<Vec<u32>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        Eratosthenes::gen_table(SMALL_PRIMES_MAX)
+            .iter_ones()
+            .map(|p| p as u32)
+            .collect()
+    })
+}
+
+/// Returns whether `n` is prime, for `n <= SMALL_LIMIT`.
+#[inline]
+pub(crate) fn is_small_prime(n: usize) -> bool {
+    debug_assert!(n <= SMALL_LIMIT);
+    SMALL_PRIMES.binary_search(&(n as u32)).is_ok()
+}
+
+/// Counts the primes <= `n`, for `n <= SMALL_LIMIT`.
+#[inline]
+pub(crate) fn small_prime_pi(n: usize) -> usize {
+    debug_assert!(n <= SMALL_LIMIT);
+    SMALL_PRIMES.partition_point(|&p| p as usize <= n)
+}
@@ -0,0 +1,85 @@
+use super::{bitset::BITS_PER_WORD, sieve::sieve_no_alloc};
+
+/// Returns the number of words a `StackSieve` needs to sieve up to `limit`.
+#[inline]
+pub const fn words_for(limit: usize) -> usize {
+    limit / BITS_PER_WORD + 1
+}
+
+/// A prime table of `WORDS` words stored inline, for repeatedly sieving
+/// small limits without heap allocation.
+///
+/// Stable Rust cannot size the array from a limit, so use `words_for`:
+/// `StackSieve::<{ words_for(1000) }>::new(1000)`.
+#[derive(Clone)]
+pub struct StackSieve<const WORDS: usize> {
+    words: [usize; WORDS],
+    limit: usize,
+}
+
+impl<const WORDS: usize> StackSieve<WORDS> {
+    /// Sieves the primes <= `limit`.
+    ///
+    /// # Panics
+    /// Panics if `WORDS` is less than `words_for(limit)`.
+    #[inline]
+    pub fn new(limit: usize) -> Self {
+        let mut res = Self {
+            words: [0; WORDS],
+            limit: 0,
+        };
+        res.sieve(limit);
+        res
+    }
+
+    /// Sieves the primes <= `limit` again, in place.
+    ///
+    /// # Panics
+    /// Panics if `WORDS` is less than `words_for(limit)`.
+    #[inline]
+    pub fn sieve(&mut self, limit: usize) {
+        sieve_no_alloc(&mut self.words, limit);
+        self.limit = limit;
+    }
+
+    /// Returns the limit of the sieve.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns whether `n` is prime.
+    ///
+    /// # Panics
+    /// Panics if `n` is greater than the limit.
+    #[inline]
+    pub fn is_prime(&self, n: usize) -> bool {
+        assert!(n <= self.limit, "out of the sieve's range");
+        self.words[n / BITS_PER_WORD] >> (n % BITS_PER_WORD) & 1 != 0
+    }
+
+    /// Returns the number of primes in the sieve.
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns an iterator over the primes in the sieve.
+    #[inline]
+    pub fn primes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit_i = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(word_i * BITS_PER_WORD + bit_i)
+            })
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use super::{
+    bitset::{BitSet, IterOnes},
+    sieve::{for_each_segment, Eratosthenes},
+};
+
+/// A table of primes up to a limit, answering primality queries in constant time.
+pub struct PrimeSieve {
+    table: BitSet,
+    limit: usize,
+}
+
+impl PrimeSieve {
+    /// Sieves the primes <= `limit`.
+    #[inline]
+    pub fn new(limit: usize) -> Self {
+        Self {
+            table: Eratosthenes::gen_table(limit),
+            limit,
+        }
+    }
+
+    /// Returns the limit of the sieve.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns whether `n` is prime.
+    ///
+    /// # Panics
+    /// Panics if `n` is greater than the limit.
+    #[inline]
+    pub fn is_prime(&self, n: usize) -> bool {
+        assert!(n <= self.limit, "out of the sieve's range");
+        unsafe { self.table.get(n) }
+    }
+
+    /// Returns whether both `p` and `q` are prime.
+    ///
+    /// # Panics
+    /// Panics if `p` or `q` is greater than the limit.
+    #[inline]
+    pub fn is_prime_pair(&self, p: usize, q: usize) -> bool {
+        assert!(p.max(q) <= self.limit, "out of the sieve's range");
+        unsafe { self.table.get(p) && self.table.get(q) }
+    }
+
+    /// Returns whether `p` and `p + 2` are both prime.
+    ///
+    /// # Panics
+    /// Panics if `p + 2` is greater than the limit.
+    #[inline]
+    pub fn is_twin_prime(&self, p: usize) -> bool {
+        self.is_prime_pair(p, p.saturating_add(2))
+    }
+
+    /// Returns whether `p` and `p + 4` are both prime.
+    ///
+    /// # Panics
+    /// Panics if `p + 4` is greater than the limit.
+    #[inline]
+    pub fn is_cousin_prime(&self, p: usize) -> bool {
+        self.is_prime_pair(p, p.saturating_add(4))
+    }
+
+    /// Returns whether `p` and `p + 6` are both prime.
+    ///
+    /// # Panics
+    /// Panics if `p + 6` is greater than the limit.
+    #[inline]
+    pub fn is_sexy_prime(&self, p: usize) -> bool {
+        self.is_prime_pair(p, p.saturating_add(6))
+    }
+
+    /// Returns whether all of `values` are prime.
+    ///
+    /// Values greater than the limit are out of the table, and treated as not prime,
+    /// so any of them makes the result false.
+    #[inline]
+    pub fn contains_all(&self, values: &[usize]) -> bool {
+        values
+            .iter()
+            .all(|&n| n <= self.limit && unsafe { self.table.get(n) })
+    }
+
+    /// Returns the primes among `values`, in their original order.
+    ///
+    /// Values greater than the limit are out of the table, and skipped as not prime,
+    /// consistently with `contains_all`.
+    #[inline]
+    pub fn filter_primes(&self, values: &[usize]) -> Vec<usize> {
+        values
+            .iter()
+            .copied()
+            .filter(|&n| n <= self.limit && unsafe { self.table.get(n) })
+            .collect()
+    }
+
+    /// Collects the primes in the sieve into a `PrimeList`.
+    ///
+    /// # Panics
+    /// Panics if the limit is greater than `u32::MAX`.
+    pub fn to_prime_list(&self) -> PrimeList {
+        assert!(self.limit <= u32::MAX as usize, "primes must fit in u32");
+        PrimeList {
+            primes: self.primes().map(|p| p as u32).collect(),
+            limit: self.limit,
+        }
+    }
+
+    /// Returns an iterator over the primes in the sieve.
+    #[inline]
+    pub fn primes(&self) -> IterOnes<'_> {
+        self.table.iter_ones()
+    }
+
+    /// Returns an iterator over the primes `p` in the sieve such that `p ≡ a (mod modulus)`.
+    ///
+    /// # Panics
+    /// Panics if `modulus` is zero.
+    #[inline]
+    pub fn primes_congruent_to(
+        &self,
+        a: usize,
+        modulus: usize,
+    ) -> impl Iterator<Item = usize> + '_ {
+        assert!(modulus != 0, "zero modulus");
+        let a = a % modulus;
+        self.primes().filter(move |p| p % modulus == a)
+    }
+}
+
+/// A sorted list of primes up to a limit, answering membership queries by binary search.
+///
+/// Unlike `PrimeSieve`, queries take `O(log k)` time for `k` primes,
+/// but the list is far smaller than the table for large limits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrimeList {
+    primes: Vec<u32>,
+    limit: usize,
+}
+
+impl PrimeList {
+    /// Collects the primes <= `limit`, sieving segment by segment.
+    ///
+    /// # Panics
+    /// Panics if `limit` is greater than `u32::MAX`.
+    pub fn new(limit: usize) -> Self {
+        assert!(limit <= u32::MAX as usize, "primes must fit in u32");
+        let mut primes = Vec::new();
+        for_each_segment(limit, |seg, low| {
+            primes.extend(seg.iter_values(low).map(|p| p as u32));
+        });
+        Self { primes, limit }
+    }
+
+    /// Returns the limit of the list.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the primes in ascending order.
+    #[inline]
+    pub fn as_slice(&self) -> &[u32] {
+        &self.primes
+    }
+
+    /// Binary searches the list for `n`, as `slice::binary_search`.
+    #[inline]
+    pub fn binary_search(&self, n: u32) -> Result<usize, usize> {
+        self.primes.binary_search(&n)
+    }
+
+    /// Returns whether `n` is in the list.
+    ///
+    /// For `n` greater than the limit, this is always false.
+    #[inline]
+    pub fn contains(&self, n: u32) -> bool {
+        self.binary_search(n).is_ok()
+    }
+}
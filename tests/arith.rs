@@ -0,0 +1,88 @@
+use std::f64::consts::PI;
+
+use rusty_primes::arith::{
+    liouville_sieve, mertens, mobius_sieve, phi_sieve, totient_inverse, totient_sum_below,
+};
+
+#[test]
+fn totient_sums() {
+    assert_eq!(totient_sum_below(0), 0);
+    assert_eq!(totient_sum_below(1), 1);
+    assert_eq!(totient_sum_below(10), 32);
+    assert_eq!(totient_sum_below(100), 3_044);
+    assert_eq!(totient_sum_below(1_000), 304_192);
+
+    // `Φ(n) = 3n^2/π^2 + O(n log n)`.
+    for &n in &[1_000, 10_000, 100_000, 1_000_000] {
+        let x = n as f64;
+        let error = totient_sum_below(n) as f64 - 3.0 * x * x / (PI * PI);
+        assert!(error.abs() < x * x.ln(), "n = {}, error = {}", n, error);
+    }
+}
+
+#[test]
+fn totient_inverses() {
+    assert_eq!(totient_inverse(4), [5, 8, 10, 12]);
+    assert_eq!(totient_inverse(1), [1, 2]);
+    assert!(totient_inverse(0).is_empty());
+    // Odd values above 1 and 14 are not totients.
+    assert!(totient_inverse(7).is_empty());
+    assert!(totient_inverse(14).is_empty());
+
+    // `φ(n) >= sqrt(n / 2)`, so every `n` with `φ(n) <= M` is at most `2M^2`.
+    const M: usize = 100;
+    let phi = phi_sieve(2 * M * M);
+    for m in 1..=M {
+        let expected: Vec<_> = (1..phi.len()).filter(|&n| phi[n] as usize == m).collect();
+        assert_eq!(totient_inverse(m), expected, "m = {}", m);
+    }
+}
+
+#[test]
+fn mobius_values() {
+    // `μ(k)` for `k = 0..=20`, with `μ(0)` set to zero.
+    assert_eq!(
+        mobius_sieve(20),
+        [0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0, -1, 1, 1, 0, -1, 0, -1, 0]
+    );
+    assert_eq!(mobius_sieve(0), [0]);
+    assert_eq!(mobius_sieve(1), [0, 1]);
+}
+
+#[test]
+fn mertens_values() {
+    assert_eq!(mertens(0), 0);
+    assert_eq!(mertens(1), 1);
+    assert_eq!(mertens(100), 1);
+    assert_eq!(mertens(1_000), 2);
+    assert_eq!(mertens(1_000_000), 212);
+
+    // The blocked sum agrees with the prefix sums of the sieved `μ`.
+    let mu = mobius_sieve(5_000);
+    let mut sum = 0;
+    for (n, &mu) in mu.iter().enumerate() {
+        sum += mu as i64;
+        assert_eq!(mertens(n), sum, "M({})", n);
+    }
+}
+
+#[test]
+fn liouville_values() {
+    let lambda = liouville_sieve(20);
+    assert_eq!(lambda[0], 0);
+    assert_eq!(lambda[1], 1);
+    assert_eq!(lambda[2], -1);
+    assert_eq!(lambda[4], 1);
+    assert_eq!(lambda[8], -1);
+    assert_eq!(lambda[12], -1);
+    assert_eq!(liouville_sieve(0), [0]);
+
+    // `λ` and `μ` agree on the squarefree integers, where `μ` is nonzero.
+    let lambda = liouville_sieve(100_000);
+    let mu = mobius_sieve(100_000);
+    for (k, (&lambda, &mu)) in lambda.iter().zip(&mu).enumerate() {
+        if mu != 0 {
+            assert_eq!(lambda, mu, "λ({})", k);
+        }
+    }
+}
@@ -0,0 +1,389 @@
+use rusty_primes::{bitset::BitSet, iter::PrimesInRange, sieve::Eratosthenes};
+
+#[test]
+fn density() {
+    for len in [1, 63, 64, 65, 1_000] {
+        assert_eq!(BitSet::new(len, true).density(), 1.0);
+        assert_eq!(BitSet::new(len, false).density(), 0.0);
+    }
+    // 25 primes <= 100 in 101 bits.
+    let table = Eratosthenes::gen_table(100);
+    assert_eq!(table.density(), 25.0 / 101.0);
+    assert!(table.is_dense(0.2));
+    assert!(!table.is_dense(0.25));
+    assert!(!BitSet::new(64, true).is_dense(1.0));
+}
+
+/// Returns a bitset of length `len` with the bits at `ones` set.
+fn from_ones(len: usize, ones: impl IntoIterator<Item = usize>) -> BitSet {
+    let mut res = BitSet::new(len, false);
+    for i in ones {
+        unsafe { res.set(i) }
+    }
+    res
+}
+
+#[test]
+fn rle_round_trip() {
+    let mut bitsets = vec![
+        BitSet::new(1, false),
+        BitSet::new(1, true),
+        BitSet::new(200, true),
+        BitSet::new(200, false),
+        from_ones(130, [0, 63, 64, 65, 127, 129]),
+        from_ones(130, (10..70).chain(100..130)),
+        Eratosthenes::gen_table(10_000),
+    ];
+    bitsets.extend((1..=130).map(|len| from_ones(len, (0..len).step_by(3))));
+    for bitset in &bitsets {
+        let restored = BitSet::from_rle(&bitset.to_rle(), bitset.len());
+        assert_eq!(restored.len(), bitset.len());
+        assert_eq!(restored.to_bytes(), bitset.to_bytes());
+    }
+    assert_eq!(from_ones(100, [3, 4, 5, 50]).to_rle(), [3, 3, 44, 1]);
+}
+
+#[test]
+fn rle_size() {
+    // The primes in a window of 10^6 just above 10^8, about one in 18.
+    const LOW: usize = 100_000_000;
+    let len = 1_000_000;
+    let window = from_ones(len, PrimesInRange::new(LOW, LOW + len - 1).map(|p| p - LOW));
+    let rle_bytes = window.to_rle().len() * 4;
+    let raw_bytes = window.to_bytes().len();
+    // Nearly every run of ones is a single prime taking 8 bytes, so the
+    // encoding is several times larger than the raw bits.
+    assert!(rle_bytes > 3 * raw_bytes, "{} vs {}", rle_bytes, raw_bytes);
+
+    // The squares are sparse enough for the encoding to pay off.
+    let squares = from_ones(len, (0..1_000).map(|i| i * i));
+    assert!(squares.to_rle().len() * 4 * 10 < squares.to_bytes().len());
+}
+
+#[test]
+fn popcount_range() {
+    let table = Eratosthenes::gen_table(1_000);
+    assert_eq!(table.popcount_range(0, table.len()), table.count_ones());
+    // Within a single word: 2, 3, 5, 7.
+    assert_eq!(table.popcount_range(0, 10), 4);
+    assert_eq!(table.popcount_range(11, 12), 1);
+    assert_eq!(table.popcount_range(12, 12), 0);
+    // Across words: the primes in [60, 200).
+    assert_eq!(table.popcount_range(60, 200), 46 - 17);
+
+    let primes: Vec<_> = table.iter_ones().collect();
+    for start in (0..=table.len()).step_by(7) {
+        for end in (start..=table.len()).step_by(13) {
+            let expected = primes.iter().filter(|&&p| start <= p && p < end).count();
+            assert_eq!(
+                table.popcount_range(start, end),
+                expected,
+                "[{}, {})",
+                start,
+                end
+            );
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "range out of bounds")]
+fn popcount_range_out_of_bounds() {
+    BitSet::new(100, true).popcount_range(0, 101);
+}
+
+/// Returns the indexes of the ones in `bitset`.
+fn ones(bitset: &BitSet) -> Vec<usize> {
+    bitset.iter_ones().collect()
+}
+
+#[test]
+fn rotate() {
+    // A pattern crossing the word boundaries of a length that is not a multiple of a word.
+    let len = 150;
+    let pattern = [0, 1, 5, 63, 64, 100, 127, 128, 149];
+    for n in [0, 1, 7, 8, 63, 64, 65, 149, len, len + 1, 3 * len + 7] {
+        let mut bitset = from_ones(len, pattern);
+        bitset.rotate_left(n);
+        let mut expected: Vec<_> = pattern.iter().map(|&i| (i + n) % len).collect();
+        expected.sort_unstable();
+        assert_eq!(ones(&bitset), expected, "rotate_left({})", n);
+
+        // Rotating back restores the pattern.
+        bitset.rotate_right(n);
+        assert_eq!(ones(&bitset), pattern, "rotate_right({})", n);
+    }
+
+    // Every length and amount on a smaller pattern.
+    for len in 1..=130 {
+        let original = from_ones(len, (0..len).filter(|i| i % 5 == 0 || i % 7 == 3));
+        for n in 0..=len {
+            let mut bitset = from_ones(len, ones(&original));
+            bitset.rotate_right(n);
+            let expected: Vec<_> = (0..len)
+                .filter(|&i| unsafe { original.get((i + n) % len) })
+                .collect();
+            assert_eq!(ones(&bitset), expected, "len {}, rotate_right({})", len, n);
+            bitset.rotate_left(n);
+            assert_eq!(ones(&bitset), ones(&original));
+        }
+    }
+}
+
+#[test]
+fn zeros_counts() {
+    const BITS: usize = usize::BITS as usize;
+    for k in 1..=3 {
+        for len in [k * BITS - 1, k * BITS, k * BITS + 1] {
+            let zeros = BitSet::new(len, false);
+            assert_eq!(zeros.trailing_zeros_count(), len);
+            assert_eq!(zeros.leading_zeros_count(), len);
+
+            for i in 0..len {
+                let bitset = from_ones(len, [i]);
+                assert_eq!(bitset.trailing_zeros_count(), i, "len {}, bit {}", len, i);
+                assert_eq!(
+                    bitset.leading_zeros_count(),
+                    len - 1 - i,
+                    "len {}, bit {}",
+                    len,
+                    i
+                );
+            }
+
+            let ones = BitSet::new(len, true);
+            assert_eq!(ones.trailing_zeros_count(), 0);
+            assert_eq!(ones.leading_zeros_count(), 0);
+        }
+    }
+}
+
+#[test]
+fn shifts() {
+    // Single bits, including shifts by whole words and past the length.
+    let len = 200;
+    for i in [0, 1, 63, 64, 65, 127, 128, 199] {
+        let bitset = from_ones(len, [i]);
+        for n in [0, 1, 2, 63, 64, 65, 128, 199, 200, 1_000] {
+            let left: Vec<_> = Some(i + n).filter(|&j| j < len).into_iter().collect();
+            assert_eq!(ones(&bitset.shift_left(n)), left, "{} << {}", i, n);
+            let right: Vec<_> = i.checked_sub(n).into_iter().collect();
+            assert_eq!(ones(&bitset.shift_right(n)), right, "{} >> {}", i, n);
+        }
+    }
+
+    // A pattern crossing word boundaries, for every shift.
+    let bitset = Eratosthenes::gen_table(len - 1);
+    let primes = ones(&bitset);
+    for n in 0..=len {
+        let left: Vec<_> = primes.iter().map(|p| p + n).filter(|&j| j < len).collect();
+        let right: Vec<_> = primes.iter().filter_map(|p| p.checked_sub(n)).collect();
+        let shifted = bitset.shift_left(n);
+        assert_eq!(shifted.len(), len);
+        assert_eq!(ones(&shifted), left, "<< {}", n);
+        assert_eq!(ones(&bitset.shift_right(n)), right, ">> {}", n);
+    }
+}
+
+#[test]
+fn and_shifted() {
+    // The smaller of each twin prime pair up to 10^5.
+    let table = Eratosthenes::gen_table(100_000);
+    assert_eq!(table.count_and_shifted(2), 1_224);
+    assert_eq!(table.and_shifted(2).count_ones(), 1_224);
+    assert_eq!(table.and_shifted(2).len(), table.len());
+
+    // Shifts within a word, by whole words, across words and past the length.
+    let len = 1_000;
+    let table = Eratosthenes::gen_table(len - 1);
+    for shift in [0, 1, 2, 6, 63, 64, 65, 128, 130, 999, 1_000, 5_000] {
+        let expected: Vec<_> = (0..len)
+            .filter(|&i| i + shift < len && unsafe { table.get(i) && table.get(i + shift) })
+            .collect();
+        assert_eq!(ones(&table.and_shifted(shift)), expected, "shift {}", shift);
+        assert_eq!(
+            table.count_and_shifted(shift),
+            expected.len(),
+            "shift {}",
+            shift
+        );
+    }
+}
+
+#[test]
+fn runs() {
+    // A run ending at the length must not run on into the unused bits.
+    let bitset = from_ones(65, 60..65);
+    assert_eq!(bitset.iter_runs().collect::<Vec<_>>(), [(60, 5)]);
+    let bitset = BitSet::new(65, true);
+    assert_eq!(bitset.iter_runs().collect::<Vec<_>>(), [(0, 65)]);
+
+    let mut bitset = BitSet::new(130, true);
+    unsafe {
+        bitset.clear(10);
+        bitset.truncate(70);
+    }
+    assert_eq!(bitset.iter_runs().collect::<Vec<_>>(), [(0, 10), (11, 59)]);
+
+    // Runs crossing word boundaries, compared with the ones.
+    let bitset = from_ones(200, (0..200).filter(|i| i % 70 < 66));
+    let runs: Vec<_> = bitset.iter_runs().collect();
+    assert_eq!(runs, [(0, 66), (70, 66), (140, 60)]);
+    let expanded: Vec<_> = runs
+        .iter()
+        .flat_map(|&(start, len)| start..start + len)
+        .collect();
+    assert_eq!(expanded, ones(&bitset));
+    assert_eq!(BitSet::new(64, false).iter_runs().next(), None);
+}
+
+#[test]
+fn padding_stays_clear() {
+    const BITS: usize = usize::BITS as usize;
+    for len in [1, 63, 64, 65, 100, 128, 129] {
+        let mut bitset = BitSet::new(200, true);
+        unsafe { bitset.truncate(len) }
+        assert_eq!(bitset.len(), len);
+        // `count_ones` reads whole words, so it counts any set padding bit.
+        assert_eq!(bitset.count_ones(), len, "truncate({})", len);
+        assert_eq!(
+            bitset.to_bytes().last(),
+            BitSet::new(len, true).to_bytes().last()
+        );
+        assert_eq!(ones(&bitset.shift_right(0)).len(), len);
+
+        let mut bitset = BitSet::new(200, true);
+        bitset.resize(len, true);
+        assert_eq!(bitset.count_ones(), len, "resize({})", len);
+        // Growing with zeros must not bring back bits cleared as padding.
+        bitset.resize(len + BITS, false);
+        assert_eq!(bitset.count_ones(), len, "resize({}) and grow", len);
+    }
+}
+
+#[cfg(feature = "checked")]
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn checked_get_out_of_range() {
+    let bitset = BitSet::new(65, true);
+    let _ = unsafe { bitset.get(65) };
+}
+
+#[test]
+fn resize() {
+    for len in [1, 63, 64, 65] {
+        for new_len in [1, 63, 64, 65, 129] {
+            for fill in [false, true] {
+                let pattern: Vec<_> = (0..len).filter(|i| i % 3 != 1).collect();
+                let mut bitset = from_ones(len, pattern.iter().copied());
+                bitset.resize(new_len, fill);
+                assert_eq!(bitset.len(), new_len);
+                let expected: Vec<_> = pattern
+                    .iter()
+                    .copied()
+                    .filter(|&i| i < new_len)
+                    .chain((len..new_len).filter(|_| fill))
+                    .collect();
+                assert_eq!(
+                    ones(&bitset),
+                    expected,
+                    "{} -> {}, fill {}",
+                    len,
+                    new_len,
+                    fill
+                );
+                assert_eq!(bitset.count_ones(), expected.len());
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "empty bitset")]
+fn resize_to_zero() {
+    BitSet::new(64, true).resize(0, false);
+}
+
+#[test]
+fn count_ones_below() {
+    for len in [1, 63, 64, 65, 130] {
+        for bitset in [BitSet::new(len, true), Eratosthenes::gen_table(len - 1)] {
+            let ones = ones(&bitset);
+            for i in [0, 1, 63, 64, 65, 128, len - 1, len]
+                .iter()
+                .copied()
+                .filter(|&i| i <= len)
+            {
+                let expected = ones.iter().filter(|&&j| j < i).count();
+                assert_eq!(bitset.count_ones_below(i), expected, "len {}, i {}", len, i);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "range out of bounds")]
+fn count_ones_below_out_of_bounds() {
+    BitSet::new(64, true).count_ones_below(65);
+}
+
+#[test]
+fn iter_values() {
+    for len in [1, 63, 64, 65, 130] {
+        let bitset = from_ones(
+            len,
+            [0, 62, 63, 64, 65, 129]
+                .iter()
+                .copied()
+                .filter(|&i| i < len),
+        );
+        for base in [0, 1, 1_000_000] {
+            let expected: Vec<_> = ones(&bitset).iter().map(|i| base + i).collect();
+            assert_eq!(
+                bitset.iter_values(base).collect::<Vec<_>>(),
+                expected,
+                "len {}, base {}",
+                len,
+                base
+            );
+        }
+        // Full words must not yield values past the length.
+        assert_eq!(
+            BitSet::new(len, true).iter_values(10).collect::<Vec<_>>(),
+            (10..10 + len).collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(BitSet::new(64, false).iter_values(5).next(), None);
+}
+
+#[test]
+fn count_ones_strided() {
+    for len in [1, 63, 64, 65, 130] {
+        for bitset in [BitSet::new(len, true), Eratosthenes::gen_table(len - 1)] {
+            let ones = ones(&bitset);
+            for start in [0, 1, 63, 64, 65, 200] {
+                // Steps shorter and longer than a word.
+                for step in (1..=70).chain([128, 1_000]) {
+                    let expected = ones
+                        .iter()
+                        .filter(|&&i| i >= start && (i - start) % step == 0)
+                        .count();
+                    assert_eq!(
+                        bitset.count_ones_strided(start, step),
+                        expected,
+                        "len {}, start {}, step {}",
+                        len,
+                        start,
+                        step
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "zero step")]
+fn count_ones_strided_zero_step() {
+    BitSet::new(64, true).count_ones_strided(0, 0);
+}
@@ -0,0 +1,74 @@
+use rusty_primes::{
+    collect::{prime_mask_bytes, primes_column, PrimeColumn},
+    is_prime, primes_between,
+};
+
+#[test]
+fn primes_between_small() {
+    assert_eq!(primes_between(2, 20), [2, 3, 5, 7, 11, 13, 17, 19]);
+    assert_eq!(primes_between(0, 1), [] as [usize; 0]);
+    assert_eq!(primes_between(7, 7), [7]);
+    assert_eq!(primes_between(8, 10), [] as [usize; 0]);
+}
+
+#[test]
+fn primes_between_large() {
+    // Ranges beyond the threshold of the whole table are sieved segment by segment.
+    for &(a, b) in &[
+        (0, 2_000_000),
+        (999_000, 1_001_000),
+        (10_000_000, 10_010_000),
+    ] {
+        let primes = primes_between(a, b);
+        let expected: Vec<_> = (a..=b).filter(|&n| is_prime(n)).collect();
+        assert_eq!(primes, expected, "[{}, {}]", a, b);
+    }
+}
+
+#[test]
+#[should_panic(expected = "empty range")]
+fn primes_between_reversed() {
+    primes_between(20, 2);
+}
+
+#[test]
+fn prime_masks() {
+    assert_eq!(prime_mask_bytes(0), [0]);
+    assert_eq!(prime_mask_bytes(1), [0, 0]);
+    assert_eq!(
+        prime_mask_bytes(12),
+        [0, 0, 1, 1, 0, 1, 0, 1, 0, 0, 0, 1, 0]
+    );
+
+    let mask = prime_mask_bytes(100_000);
+    assert_eq!(mask.len(), 100_001);
+    assert_eq!(mask.iter().map(|&b| b as usize).sum::<usize>(), 9_592);
+    for (n, &b) in mask.iter().enumerate() {
+        assert_eq!(b == 1, is_prime(n), "{}", n);
+    }
+}
+
+#[test]
+fn prime_columns() {
+    assert_eq!(primes_column(1), PrimeColumn::default());
+    assert_eq!(
+        primes_column(20),
+        PrimeColumn {
+            values: vec![2, 3, 5, 7, 11, 13, 17, 19],
+            min: Some(2),
+            max: Some(19),
+            count: 8,
+        }
+    );
+
+    let column = primes_column(1_000_000);
+    assert_eq!(column.count, 78_498);
+    assert_eq!(column.values.len(), column.count);
+    assert_eq!(column.min, Some(2));
+    assert_eq!(column.max, Some(999_983));
+    let expected: Vec<_> = primes_between(0, 1_000_000)
+        .into_iter()
+        .map(|p| p as u32)
+        .collect();
+    assert_eq!(column.values, expected);
+}
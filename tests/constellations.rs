@@ -0,0 +1,75 @@
+use rusty_primes::constellations::{
+    count_constellation, nth_twin_prime, prime_k_tuples, prime_tuples_count, safe_prime_count,
+    safe_primes_below,
+};
+
+#[test]
+fn prime_tuple_counts() {
+    // Twin prime pairs up to 10^5.
+    assert_eq!(prime_tuples_count(100_000, &[2]), 1_224);
+    // Prime triplets `(p, p + 2, p + 6)` up to 10^5.
+    assert_eq!(prime_tuples_count(100_000, &[2, 6]), 259);
+    assert_eq!(prime_tuples_count(100_000, &[]), 9_592);
+    assert_eq!(prime_tuples_count(1, &[2]), 0);
+    assert_eq!(prime_tuples_count(10, &[20]), 0);
+
+    for &offsets in &[&[2][..], &[2, 6], &[4, 6], &[2, 6, 8]] {
+        let mut pattern = vec![0];
+        pattern.extend_from_slice(offsets);
+        for &n in &[10, 100, 1_000, 12_345] {
+            assert_eq!(
+                prime_tuples_count(n, offsets),
+                count_constellation(n, &pattern)
+            );
+        }
+    }
+    // Prime quadruplets `(p, p + 2, p + 6, p + 8)` below 10^6.
+    assert_eq!(count_constellation(1_000_000, &[0, 2, 6, 8]), 166);
+}
+
+#[test]
+fn safe_primes() {
+    // 17 rather than 9: the list of 9 stops at 167, short of the limit.
+    assert_eq!(safe_prime_count(500), 17);
+    assert_eq!(
+        safe_primes_below(500),
+        [5, 7, 11, 23, 47, 59, 83, 107, 167, 179, 227, 263, 347, 359, 383, 467, 479]
+    );
+    // Strictly below `n`.
+    assert_eq!(safe_primes_below(479).last(), Some(&467));
+    assert_eq!(safe_prime_count(5), 0);
+    assert_eq!(safe_prime_count(6), 1);
+    assert_eq!(
+        safe_prime_count(1_000_000),
+        safe_primes_below(1_000_000).len()
+    );
+}
+
+#[test]
+fn prime_k_tuple_values() {
+    assert_eq!(prime_k_tuples(100, &[0, 2]), [3, 5, 11, 17, 29, 41, 59, 71]);
+    assert_eq!(prime_k_tuples(1_000, &[0, 2, 6, 8]), [5, 11, 101, 191, 821]);
+    assert_eq!(prime_k_tuples(100, &[0, 4, 6]), [7, 13, 37, 67, 97]);
+    // Only `p` is bounded by `n`, so 71 is found although 73 exceeds the limit.
+    assert_eq!(prime_k_tuples(71, &[0, 2]).last(), Some(&71));
+    assert_eq!(
+        prime_k_tuples(100_000, &[0, 2, 6]).len(),
+        prime_tuples_count(100_006, &[2, 6])
+    );
+}
+
+#[test]
+#[should_panic(expected = "empty pattern")]
+fn prime_k_tuples_empty_pattern() {
+    prime_k_tuples(100, &[]);
+}
+
+#[test]
+fn nth_twin_primes() {
+    assert_eq!(nth_twin_prime(0), None);
+    assert_eq!(nth_twin_prime(1), Some((3, 5)));
+    assert_eq!(nth_twin_prime(2), Some((5, 7)));
+    assert_eq!(nth_twin_prime(10), Some((107, 109)));
+    // The last twin pair up to 10^5, the 1224th.
+    assert_eq!(nth_twin_prime(1_224), Some((99_989, 99_991)));
+}
@@ -0,0 +1,336 @@
+use rusty_primes::{
+    counting::{
+        chebyshev_psi, chebyshev_theta, count_primes_congruent,
+        count_primes_in_arithmetic_progression, nth_prime, pi_vs_li, prime_density_windows,
+        prime_pi, prime_pi_running_count, prime_power_sum, prime_product_mod,
+        prime_spiral_diagonals, prime_sum, segment_prime_sum,
+    },
+    is_prime,
+    lehmer::LehmerPi,
+    prime_pi_detailed,
+    sieve::{
+        Eratosthenes, OddSegmentedEratosthenes, ParallelSegmentedEratosthenes,
+        SegmentedEratosthenes, Sieve,
+    },
+};
+
+#[test]
+fn primes_in_arithmetic_progression() {
+    // The classes are not equal: the primes ≡ 3 (mod 4) lead up to 100,
+    // as in Chebyshev's bias.
+    assert_eq!(count_primes_in_arithmetic_progression(1, 4, 100), 11);
+    assert_eq!(count_primes_in_arithmetic_progression(3, 4, 100), 13);
+    // With 2, the classes add up to `π(100)`.
+    assert_eq!(count_primes_in_arithmetic_progression(2, 4, 100), 1);
+    assert_eq!(count_primes_in_arithmetic_progression(0, 1, 100), 25);
+    // `gcd(a, d) > 1` leaves at most one prime.
+    assert_eq!(count_primes_in_arithmetic_progression(0, 4, 100), 0);
+    assert_eq!(count_primes_in_arithmetic_progression(3, 6, 100), 1);
+
+    for &(a, d) in &[(1, 4), (3, 4), (1, 6), (5, 6), (7, 30), (0, 7)] {
+        for &n in &[0, 1, 2, 100, 10_000] {
+            assert_eq!(
+                count_primes_in_arithmetic_progression(a, d, n),
+                count_primes_congruent(n, a, d)
+            );
+        }
+    }
+
+    // Moduli past `usize::MAX / 2`, for which `a + m` overflows: only `p = a` is counted.
+    for m in [usize::MAX / 2 + 1, usize::MAX - 1, usize::MAX] {
+        assert_eq!(count_primes_congruent(1_000_000, 999_983, m), 1);
+        assert_eq!(count_primes_congruent(1_000_000, 999_981, m), 0);
+        assert_eq!(count_primes_congruent(1_000_000, m - 1, m), 0);
+    }
+}
+
+#[test]
+fn running_prime_count() {
+    let pi: Vec<_> = prime_pi_running_count(10).collect();
+    assert_eq!(pi, [0, 0, 1, 2, 2, 3, 3, 4, 4, 4, 4]);
+    assert_eq!(prime_pi_running_count(0).collect::<Vec<_>>(), [0]);
+
+    let n = 10_000;
+    assert_eq!(prime_pi_running_count(n).count(), n + 1);
+    for (k, pi) in prime_pi_running_count(n).enumerate().step_by(97) {
+        assert_eq!(pi, prime_pi(k), "π({})", k);
+    }
+}
+
+#[test]
+fn prime_sums() {
+    assert_eq!(prime_sum(10), 17);
+    assert_eq!(prime_sum(100), 1_060);
+    assert_eq!(prime_sum(1), 0);
+    assert_eq!(prime_sum(2), 2);
+    assert_eq!(prime_sum(1_000_000), 37_550_402_023);
+
+    assert_eq!(Eratosthenes::prime_sum(100), 1_060);
+    assert_eq!(SegmentedEratosthenes::prime_sum(100), 1_060);
+    for n in [0, 1, 2, 3, 1_000, 65_537, 1_000_000] {
+        let sum = prime_sum(n);
+        assert_eq!(SegmentedEratosthenes::prime_sum(n), sum, "n = {}", n);
+        assert_eq!(OddSegmentedEratosthenes::prime_sum(n), sum, "n = {}", n);
+        assert_eq!(LehmerPi::prime_sum(n), sum, "n = {}", n);
+    }
+}
+
+/// Returns the primorial `n#` modulo `m`, by trial division.
+fn primorial_mod(n: u128, m: u128) -> u128 {
+    (2..=n)
+        .filter(|&p| (2..p).take_while(|d| d * d <= p).all(|d| p % d != 0))
+        .fold(1 % m, |res, p| res * p % m)
+}
+
+#[test]
+fn primorials_mod() {
+    const MOD: u64 = 1_000_000_007;
+    // 2 * 3 * 5 * 7
+    assert_eq!(prime_product_mod(10, MOD), 210);
+    assert_eq!(prime_product_mod(1, MOD), 1);
+    assert_eq!(prime_product_mod(10, 1), 0);
+    assert_eq!(
+        prime_product_mod(1_000, MOD) as u128,
+        primorial_mod(1_000, MOD as u128)
+    );
+
+    // A prime modulus divides the primorial from itself on.
+    assert_eq!(prime_product_mod(96, 97) as u128, primorial_mod(96, 97));
+    assert_ne!(prime_product_mod(96, 97), 0);
+    assert_eq!(prime_product_mod(97, 97), 0);
+    // The largest prime below 2^64, where the products need 128 bits.
+    let p = 18_446_744_073_709_551_557;
+    assert_eq!(
+        prime_product_mod(100, p) as u128,
+        primorial_mod(100, p as u128)
+    );
+}
+
+#[test]
+fn nth_primes() {
+    assert_eq!(nth_prime(1), 2);
+    assert_eq!(nth_prime(1_000), 7_919);
+    // Around the ends of `SMALL_PRIMES` and `small_primes`.
+    assert_eq!(nth_prime(54), 251);
+    assert_eq!(nth_prime(55), 257);
+    assert_eq!(nth_prime(10_000), 104_729);
+    assert_eq!(nth_prime(10_001), 104_743);
+    assert_eq!(nth_prime(1_000_000), 15_485_863);
+}
+
+#[test]
+#[should_panic(expected = "primes are counted from 1")]
+fn zeroth_prime() {
+    nth_prime(0);
+}
+
+#[test]
+fn prime_power_sums() {
+    assert_eq!(prime_power_sum::<2>(10), 4 + 9 + 25 + 49);
+    assert_eq!(prime_power_sum::<0>(100), 25);
+    assert_eq!(prime_power_sum::<1>(1_000) as u64, prime_sum(1_000));
+    assert_eq!(prime_power_sum::<3>(10), 8 + 27 + 125 + 343);
+    assert_eq!(prime_power_sum::<2>(1), 0);
+}
+
+/// Asserts that `a` and `b` agree to a relative error of `1e-12`.
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() <= 1e-12 * b.abs(), "{} != {}", a, b);
+}
+
+#[test]
+fn chebyshev_theta_values() {
+    // `ϑ(100)` is about 83.73. The value 94 sometimes quoted for it is `ψ(100)`,
+    // which also counts the prime powers.
+    assert_close(chebyshev_theta(100.0), 83.728_390_399_063_93);
+    assert_close(chebyshev_theta(10.0), 5.347_107_530_717_468);
+    assert_close(chebyshev_theta(1_000.0), 956.245_265_120_058_9);
+    assert_close(chebyshev_theta(10_000.0), 9_895.991_379_156_982);
+    // Only the integer part of `x` matters.
+    assert_eq!(chebyshev_theta(100.9), chebyshev_theta(100.0));
+    assert_eq!(chebyshev_theta(1.9), 0.0);
+    assert_eq!(chebyshev_theta(-1.0), 0.0);
+    assert_eq!(chebyshev_theta(f64::NAN), 0.0);
+}
+
+#[test]
+#[should_panic(expected = "infinite limit")]
+fn chebyshev_theta_infinite() {
+    chebyshev_theta(f64::INFINITY);
+}
+
+#[test]
+fn chebyshev_psi_values() {
+    assert_close(chebyshev_psi(10.0), 7.832_014_180_505_469);
+    assert_close(chebyshev_psi(100.0), 94.045_311_229_357_4);
+    assert_close(chebyshev_psi(10_000.0), 10_013.396_693_263_11);
+    assert_eq!(chebyshev_psi(1.9), 0.0);
+
+    // `ψ(x) / x -> 1`, the prime number theorem, with `ψ >= ϑ` along the way.
+    let mut prev_error = f64::INFINITY;
+    for &x in &[100.0, 10_000.0, 1_000_000.0] {
+        let psi = chebyshev_psi(x);
+        assert!(psi >= chebyshev_theta(x));
+        let error = (psi / x - 1.0).abs();
+        assert!(error < prev_error, "x = {}", x);
+        prev_error = error;
+    }
+    assert!(prev_error < 1e-3);
+}
+
+#[test]
+fn segment_prime_sums() {
+    assert_eq!(segment_prime_sum(1, 100), prime_sum(100));
+    assert_eq!(segment_prime_sum(0, 1_000_000), prime_sum(1_000_000));
+    assert_eq!(segment_prime_sum(100, 1), 0);
+    assert_eq!(segment_prime_sum(97, 97), 97);
+    for &(low, high) in &[(2, 2), (3, 10), (1_000, 5_000), (99_990, 100_030)] {
+        assert_eq!(
+            segment_prime_sum(low, high),
+            prime_sum(high) - prime_sum(low - 1),
+            "[{}, {}]",
+            low,
+            high
+        );
+    }
+}
+
+/// Counts the primes on the diagonals of the `n × n` Ulam spiral, laid out cell by cell.
+fn spiral_diagonals_by_layout(n: usize) -> usize {
+    let len = n * n;
+    let mut cells = Vec::with_capacity(len);
+    let (mut x, mut y) = (0i64, 0i64);
+    let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+    let mut step = 1;
+    'spiral: for turn in 0.. {
+        let (dx, dy) = directions[turn % 4];
+        for _ in 0..step {
+            cells.push((x, y));
+            if cells.len() == len {
+                break 'spiral;
+            }
+            x += dx;
+            y += dy;
+        }
+        if turn % 2 == 1 {
+            step += 1;
+        }
+    }
+    let min_x = cells.iter().map(|c| c.0).min().unwrap();
+    let min_y = cells.iter().map(|c| c.1).min().unwrap();
+    cells
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(x, y))| {
+            let (i, j) = (x - min_x, y - min_y);
+            i == j || i + j == n as i64 - 1
+        })
+        .filter(|&(v, _)| is_prime(v + 1))
+        .count()
+}
+
+#[test]
+fn ulam_spiral_diagonals() {
+    // Ulam's original 7 × 7 spiral.
+    assert_eq!(prime_spiral_diagonals(7), 8);
+    assert_eq!(prime_spiral_diagonals(0), 0);
+    assert_eq!(prime_spiral_diagonals(1), 0);
+    for n in 1..=60 {
+        assert_eq!(
+            prime_spiral_diagonals(n),
+            spiral_diagonals_by_layout(n),
+            "n = {}",
+            n
+        );
+    }
+}
+
+#[test]
+fn pi_against_li() {
+    // `li(10^k)` for `k = 2, 3, 6`.
+    for &(n, pi, li) in &[
+        (100, 25, 30.126_141_584_0),
+        (1_000, 168, 177.609_658_040_9),
+        (1_000_000, 78_498, 78_627.549_159_462_2),
+    ] {
+        let (actual_pi, actual_li, error) = pi_vs_li(n);
+        assert_eq!(actual_pi, pi);
+        assert!(
+            (actual_li - li).abs() < 1e-6 * li,
+            "li({}) = {}",
+            n,
+            actual_li
+        );
+        assert_eq!(error, pi as f64 - actual_li);
+        assert!(error < 0.0);
+    }
+    let (pi, li, error) = pi_vs_li(1);
+    assert_eq!(pi, 0);
+    assert_eq!(li, f64::NEG_INFINITY);
+    assert_eq!(error, f64::INFINITY);
+}
+
+#[test]
+fn density_windows() {
+    assert_eq!(
+        prime_density_windows(100, 10),
+        [4, 4, 2, 2, 3, 2, 2, 3, 2, 1, 0]
+    );
+    // The last window is cut short at `n`.
+    assert_eq!(prime_density_windows(99, 10).len(), 10);
+    assert_eq!(prime_density_windows(102, 10)[10], 1);
+    assert_eq!(prime_density_windows(0, 10), [0]);
+
+    let n = 1_000_000;
+    let windows = prime_density_windows(n, 1_000);
+    assert_eq!(windows.len(), 1_001);
+    assert_eq!(windows.iter().sum::<usize>(), 78_498);
+    assert_eq!(windows[0], 168);
+    let ones: Vec<_> = prime_density_windows(1_000, 1)
+        .into_iter()
+        .map(|c| c == 1)
+        .collect();
+    assert_eq!(ones.iter().filter(|&&p| p).count(), 168);
+    assert!(ones.iter().enumerate().all(|(k, &p)| p == is_prime(k)));
+}
+
+#[test]
+#[should_panic(expected = "empty window")]
+fn density_windows_zero_width() {
+    prime_density_windows(100, 0);
+}
+
+#[test]
+fn detailed_prime_pi() {
+    // Limits up to 256 are looked up in the small table, larger ones counted by Lehmer's method.
+    let result = prime_pi_detailed(100);
+    assert_eq!((result.count, result.limit), (25, 100));
+    assert_eq!(result.algorithm, "small table");
+    assert_eq!(prime_pi_detailed(256).algorithm, "small table");
+    let result = prime_pi_detailed(1_000_000);
+    assert_eq!((result.count, result.limit), (78_498, 1_000_000));
+    assert_eq!(result.algorithm, "LehmerPi");
+    assert_eq!(prime_pi_detailed(257).algorithm, "LehmerPi");
+
+    // Each sieve reports its own name.
+    let n = 10_000;
+    for result in [
+        Eratosthenes::prime_pi_detailed(n),
+        SegmentedEratosthenes::prime_pi_detailed(n),
+        OddSegmentedEratosthenes::prime_pi_detailed(n),
+        ParallelSegmentedEratosthenes::prime_pi_detailed(n),
+        LehmerPi::prime_pi_detailed(n),
+    ] {
+        assert_eq!(
+            (result.count, result.limit),
+            (1_229, n),
+            "{}",
+            result.algorithm
+        );
+    }
+    assert_eq!(Eratosthenes::prime_pi_detailed(n).algorithm, "Eratosthenes");
+    assert_eq!(
+        OddSegmentedEratosthenes::prime_pi_detailed(n).algorithm,
+        "OddSegmentedEratosthenes"
+    );
+}
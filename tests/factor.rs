@@ -0,0 +1,134 @@
+use rusty_primes::factor::{
+    coprime_count, largest_prime_factor, largest_prime_factor_via_spf, FactorIter, Factorization,
+    SmallestPrimeFactorSieve,
+};
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[test]
+fn largest_prime_factors() {
+    assert_eq!(largest_prime_factor(12), 3);
+    assert_eq!(largest_prime_factor(100), 5);
+    assert_eq!(largest_prime_factor(1), 1);
+    for p in [2, 7919, 1_000_000_007, 18_446_744_073_709_551_557] {
+        assert_eq!(largest_prime_factor(p), p);
+    }
+    // A semiprime with two factors beyond trial division.
+    assert_eq!(
+        largest_prime_factor(1_000_000_007 * 998_244_353),
+        1_000_000_007
+    );
+
+    let spf = SmallestPrimeFactorSieve::new(10_000);
+    for n in 1..=10_000 {
+        assert_eq!(
+            largest_prime_factor(n as u64),
+            spf.largest_prime_factor(n) as u64,
+            "{}",
+            n
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "zero has no prime factors")]
+fn largest_prime_factor_of_zero() {
+    largest_prime_factor(0);
+}
+
+#[test]
+fn largest_prime_factors_via_spf() {
+    let spf = SmallestPrimeFactorSieve::new(10_000);
+    let table = spf.as_slice();
+    assert_eq!(largest_prime_factor_via_spf(12, table), 3);
+    assert_eq!(largest_prime_factor_via_spf(100, table), 5);
+    assert_eq!(largest_prime_factor_via_spf(1, table), 1);
+    assert_eq!(largest_prime_factor_via_spf(9_973, table), 9_973);
+    for n in 1..=10_000 {
+        assert_eq!(
+            largest_prime_factor_via_spf(n, table),
+            largest_prime_factor(n),
+            "{}",
+            n
+        );
+    }
+    // Out of the table, or without one, it falls back to factoring `n`.
+    assert_eq!(largest_prime_factor_via_spf(10_001, table), 137);
+    assert_eq!(
+        largest_prime_factor_via_spf(1_000_000_007, table),
+        1_000_000_007
+    );
+    assert_eq!(largest_prime_factor_via_spf(12, &[]), 3);
+}
+
+#[test]
+#[should_panic(expected = "zero has no prime factors")]
+fn largest_prime_factor_via_spf_of_zero() {
+    largest_prime_factor_via_spf(0, SmallestPrimeFactorSieve::new(10).as_slice());
+}
+
+#[test]
+fn coprime_counts() {
+    // {1, 5, 7}
+    assert_eq!(coprime_count(10, 6), 3);
+    assert_eq!(coprime_count(10, 1), 10);
+    assert_eq!(coprime_count(10, 0), 1);
+    assert_eq!(coprime_count(0, 6), 0);
+    // `n = m` gives `φ(m)`.
+    assert_eq!(coprime_count(30_030, 30_030), 5_760);
+
+    for m in 1..60 {
+        for n in 0..200 {
+            let expected = (1..=n).filter(|&k| gcd(k, m) == 1).count();
+            assert_eq!(coprime_count(n, m), expected, "({}, {})", n, m);
+        }
+    }
+}
+
+#[test]
+fn factor_iter() {
+    assert_eq!(
+        FactorIter::new(360).collect::<Vec<_>>(),
+        [(2, 3), (3, 2), (5, 1)]
+    );
+    assert_eq!(FactorIter::new(1).next(), None);
+    // Factors beyond trial division by the small primes.
+    let n = 2 * 2 * 1_000_003 * 998_244_353;
+    assert_eq!(
+        FactorIter::new(n).collect::<Vec<_>>(),
+        [(2, 2), (1_000_003, 1), (998_244_353, 1)]
+    );
+    assert_eq!(
+        FactorIter::new(u64::MAX).collect::<Vec<_>>(),
+        [
+            (3, 1),
+            (5, 1),
+            (17, 1),
+            (257, 1),
+            (641, 1),
+            (65_537, 1),
+            (6_700_417, 1)
+        ]
+    );
+
+    for n in (1..100_000).chain(u64::MAX - 1_000..u64::MAX) {
+        assert_eq!(
+            FactorIter::new(n).collect::<Vec<_>>(),
+            Factorization::new(n).factors(),
+            "{}",
+            n
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "zero has no prime factors")]
+fn factor_iter_of_zero() {
+    FactorIter::new(0);
+}
@@ -0,0 +1,67 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rusty_primes::{counting::first_n_primes, nth_prime, sieve::sieve_memory_estimate};
+
+/// The system allocator, tracking the bytes in use and their peak.
+struct Tracking;
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Tracking {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let in_use = IN_USE.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(in_use, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        IN_USE.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Tracking = Tracking;
+
+/// Returns the result of `f` and the peak number of bytes it allocated on top of those in use.
+fn peak_bytes<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let base = IN_USE.load(Ordering::SeqCst);
+    PEAK.store(base, Ordering::SeqCst);
+    let res = f();
+    (res, PEAK.load(Ordering::SeqCst) - base)
+}
+
+/// The only test of this binary, so that no other test allocates while measuring.
+#[test]
+fn first_n_primes_memory() {
+    assert!(first_n_primes(0).is_empty());
+    assert_eq!(first_n_primes(10), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+    let count = 200_000;
+    let (primes, peak) = peak_bytes(|| first_n_primes(count));
+    let (last, naive_peak) = peak_bytes(|| nth_prime(count));
+    assert_eq!(primes.len(), count);
+    assert_eq!(*primes.last().unwrap(), last);
+    for (i, &p) in primes.iter().enumerate().step_by(9_973) {
+        assert_eq!(p, nth_prime(i + 1));
+    }
+
+    // Apart from the result, only the base primes and a segment are allocated,
+    // whereas `nth_prime` allocates the whole table up to the bound, which is
+    // already larger than the table up to the last prime.
+    let working = peak - count * std::mem::size_of::<usize>();
+    assert!(naive_peak >= sieve_memory_estimate(last));
+    assert!(
+        working * 10 < sieve_memory_estimate(last),
+        "{} bytes besides the result, {} for the table",
+        working,
+        sieve_memory_estimate(last)
+    );
+}
@@ -0,0 +1,52 @@
+use rusty_primes::{
+    gaps::{average_prime_gap, first_gap_at_least, nth_prime_gap, prime_gaps},
+    nth_prime,
+};
+
+#[test]
+fn nth_prime_gap_small() {
+    assert_eq!(nth_prime_gap(1), 1);
+    assert_eq!(nth_prime_gap(2), 2);
+}
+
+#[test]
+fn first_gap_of_six() {
+    // 23 is the 9th prime and 29 the 10th.
+    let n = (1..).find(|&n| nth_prime_gap(n) == 6).unwrap();
+    assert_eq!((nth_prime(n), nth_prime(n + 1)), (23, 29));
+
+    let i = prime_gaps(100).iter().position(|&gap| gap == 6).unwrap();
+    assert_eq!(i + 1, n);
+}
+
+#[test]
+fn prime_gaps_below_limit() {
+    assert!(prime_gaps(3).is_empty());
+    assert_eq!(prime_gaps(30), [1, 2, 2, 4, 2, 4, 2, 4, 6]);
+    // 29 is not below the limit.
+    assert_eq!(prime_gaps(29), [1, 2, 2, 4, 2, 4, 2, 4]);
+}
+
+#[test]
+fn first_gaps_at_least() {
+    assert_eq!(first_gap_at_least(8, 1_000), Some((8, 89)));
+    assert_eq!(first_gap_at_least(72, 1_000_000), Some((72, 31_397)));
+    // The prime after the gap must be below the limit: 31469 follows 31397.
+    assert_eq!(first_gap_at_least(72, 31_470), Some((72, 31_397)));
+    assert_eq!(first_gap_at_least(72, 31_469), None);
+    assert_eq!(first_gap_at_least(1, 4), Some((1, 2)));
+    assert_eq!(first_gap_at_least(1, 3), None);
+    assert_eq!(first_gap_at_least(100, 100_000), None);
+}
+
+#[test]
+fn average_prime_gaps() {
+    assert_eq!(average_prime_gap(0), None);
+    assert_eq!(average_prime_gap(2), None);
+    assert_eq!(average_prime_gap(3), Some(1.0));
+    // 25 primes up to 100, from 2 to 97.
+    assert_eq!(average_prime_gap(100), Some(95.0 / 24.0));
+    assert_eq!(average_prime_gap(96), Some(87.0 / 23.0));
+    // 78498 primes up to 10^6, the last one 999983.
+    assert_eq!(average_prime_gap(1_000_000), Some(999_981.0 / 78_497.0));
+}
@@ -0,0 +1,90 @@
+use rusty_primes::{
+    iter::{primes_in_ap, HeapSieve, LazyPrimeIter, PrimesInAP, PrimesInRange},
+    sieve::Eratosthenes,
+};
+
+#[test]
+fn heap_sieve() {
+    let expected: Vec<_> = Eratosthenes::gen_table(10_000)
+        .iter_ones()
+        .take(1_000)
+        .collect();
+    assert_eq!(expected.len(), 1_000);
+    assert_eq!(HeapSieve::new().take(1_000).collect::<Vec<_>>(), expected);
+    assert_eq!(
+        LazyPrimeIter::new().take(1_000).collect::<Vec<_>>(),
+        expected
+    );
+}
+
+#[test]
+fn filter_residue() {
+    // Ranges spanning many windows of `sqrt(high)`, starting at and away from 0.
+    for &(low, high) in &[(0, 20_000), (995_000, 1_050_000), (10, 10), (500, 100)] {
+        let primes: Vec<_> = PrimesInRange::new(low, high).collect();
+        for &m in &[1, 4, 6, 63, 64, 65, 1_000] {
+            // Every class for the word-pattern path, a sample for the per-bit one.
+            let classes: Vec<_> = if m < 64 {
+                (0..m).collect()
+            } else {
+                vec![0, 1, 3, m / 2 + 1, m - 1]
+            };
+            for a in classes {
+                let expected: Vec<_> = primes.iter().copied().filter(|p| p % m == a).collect();
+                let actual: Vec<_> = PrimesInRange::new(low, high).filter_residue(a, m).collect();
+                assert_eq!(actual, expected, "[{}, {}], {} mod {}", low, high, a, m);
+            }
+        }
+    }
+    // Moduli past `usize::MAX / 2`, for which `a + m` overflows: only `p = a` is kept.
+    for m in [usize::MAX / 2 + 1, usize::MAX - 1, usize::MAX] {
+        let primes: Vec<_> = PrimesInRange::new(995_000, 1_050_000)
+            .filter_residue(999_983, m)
+            .collect();
+        assert_eq!(primes, [999_983]);
+        assert_eq!(
+            PrimesInRange::new(995_000, 1_050_000)
+                .filter_residue(m - 1, m)
+                .next(),
+            None
+        );
+    }
+    // The residue is reduced modulo `m`.
+    assert_eq!(
+        PrimesInRange::new(0, 100)
+            .filter_residue(7, 6)
+            .collect::<Vec<_>>(),
+        [7, 13, 19, 31, 37, 43, 61, 67, 73, 79, 97]
+    );
+}
+
+#[test]
+fn primes_in_progressions() {
+    assert_eq!(
+        primes_in_ap(1, 4, 10),
+        [5, 13, 17, 29, 37, 41, 53, 61, 73, 89]
+    );
+    assert_eq!(primes_in_ap(0, 1, 5), [2, 3, 5, 7, 11]);
+    assert_eq!(primes_in_ap(3, 10, 0), []);
+
+    // With `gcd(a, d) > 1`, only the GCD itself can be prime, and only if it is a term.
+    assert_eq!(primes_in_ap(2, 4, 10), [2]);
+    assert_eq!(primes_in_ap(5, 5, 10), [5]);
+    assert_eq!(primes_in_ap(6, 4, 10), []);
+    assert_eq!(primes_in_ap(0, 3, 10), [3]);
+    assert_eq!(primes_in_ap(0, 6, 10), []);
+
+    // Terms past the initial limit of 2^16, which regenerate the sieve.
+    let table = Eratosthenes::gen_table(3_000_000);
+    for &(a, d) in &[(1, 1_000), (999, 1_000), (100_003, 2)] {
+        let expected: Vec<_> = (a..=3_000_000)
+            .step_by(d)
+            .filter(|&n| unsafe { table.get(n) })
+            .take(300)
+            .collect();
+        assert_eq!(expected.len(), 300);
+        assert!(*expected.last().unwrap() > 1 << 16);
+        assert_eq!(primes_in_ap(a, d, 300), expected, "{} + {}k", a, d);
+        assert_eq!(PrimesInAP::new(a, d).nth(299), expected.last().copied());
+    }
+}
@@ -0,0 +1,67 @@
+use rusty_primes::{
+    lehmer::LehmerPi,
+    sieve::{SegmentedEratosthenes, Sieve},
+};
+
+#[test]
+fn power_of_ten_eight() {
+    assert_eq!(
+        LehmerPi::prime_pi(100_000_000),
+        SegmentedEratosthenes::prime_pi(100_000_000)
+    );
+}
+
+#[test]
+fn power_of_ten_ten() {
+    assert_eq!(LehmerPi::prime_pi(10_000_000_000), 455_052_511);
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --ignored"]
+fn power_of_ten_twelve() {
+    assert_eq!(LehmerPi::prime_pi(1_000_000_000_000), 37_607_912_018);
+}
+
+/// Checks the limits around the switch from sieving to Lehmer's formula at `2^16`.
+#[test]
+fn sieve_threshold() {
+    for n in (65_500..65_600).chain([1 << 17, 1 << 20]) {
+        assert_eq!(
+            LehmerPi::prime_pi(n),
+            SegmentedEratosthenes::prime_pi(n),
+            "π({})",
+            n
+        );
+    }
+}
+
+#[test]
+fn small_limits() {
+    for n in 0..2_000 {
+        assert_eq!(
+            LehmerPi::prime_pi(n),
+            SegmentedEratosthenes::prime_pi(n),
+            "π({})",
+            n
+        );
+    }
+}
+
+/// Checks limits drawn by a fixed-seed xorshift, so that failures are reproducible.
+#[test]
+fn random_limits() {
+    const MAX: u64 = 5_000_000;
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    for _ in 0..40 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let n = (state % (MAX + 1)) as usize;
+        assert_eq!(
+            LehmerPi::prime_pi(n),
+            SegmentedEratosthenes::prime_pi(n),
+            "π({})",
+            n
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use rusty_primes::{
+    is_prime,
+    sieve::{available_threads, ParallelSegmentedEratosthenes, SegmentedEratosthenes, Sieve},
+};
+
+/// Checks every thread count up to 8 against the serial sieve, including one
+/// thread, as on a platform where `available_parallelism` reports one core or fails.
+fn check(n: usize) {
+    let serial = SegmentedEratosthenes::prime_pi(n);
+    for threads in 0..=8 {
+        assert_eq!(
+            ParallelSegmentedEratosthenes::prime_pi_with_threads(n, threads),
+            serial,
+            "π({}) with {} threads",
+            n,
+            threads
+        );
+    }
+}
+
+#[test]
+fn available_threads_is_positive() {
+    assert!(available_threads() >= 1);
+}
+
+#[test]
+fn small_limits() {
+    for n in 0..=1_000 {
+        check(n);
+    }
+}
+
+/// Checks limits whose chunks of `(sqrt(n), n]` end at a prime,
+/// so that primes at the ends of the chunks are neither lost nor counted twice.
+#[test]
+fn chunk_boundaries() {
+    for &s in &[100, 1_000] {
+        for threads in 2..=8 {
+            // Chunks are `ceil((n - s) / threads)` long, starting at `s + 1`.
+            let chunk_end = |n: usize| s + (n - s - 1) / threads + 1;
+            let limits = (s * s..(s + 1) * (s + 1))
+                .filter(|&n| is_prime(chunk_end(n)))
+                .take(3);
+            for n in limits {
+                for n in n - 1..=n + 1 {
+                    check(n);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn larger_limits() {
+    for &n in &[1_000_003, 9_999_991, 10_000_000] {
+        check(n);
+    }
+}
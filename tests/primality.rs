@@ -0,0 +1,176 @@
+use rusty_primes::primality::{
+    fibonacci_primes, is_fibonacci_prime, is_prime, is_prime_power, is_prime_sequence,
+    is_prime_u64, is_strong_pseudoprime, lucas_lehmer, mersenne_prime_exponents, next_prime,
+    perfect_power, prev_prime,
+};
+
+#[test]
+fn strong_pseudoprimes() {
+    // 2047 = 23 * 89 is the smallest strong pseudoprime to base 2.
+    assert!(is_strong_pseudoprime(2047, 2));
+    assert!(!is_prime_u64(2047));
+    assert!(!is_strong_pseudoprime(2047, 3));
+
+    // 3215031751 = 151 * 751 * 28351 is the smallest strong pseudoprime
+    // to all of the bases 2, 3, 5 and 7, but base 11 exposes it.
+    let n = 3_215_031_751;
+    assert!(!is_prime_u64(n));
+    for base in [2, 3, 5, 7] {
+        assert!(is_strong_pseudoprime(n, base), "base {}", base);
+    }
+    assert!(!is_strong_pseudoprime(n, 11));
+
+    // Odd primes pass for every base.
+    for p in [3, 5, 97, 1_000_003] {
+        for base in 2..20 {
+            assert!(is_strong_pseudoprime(p, base), "{} to base {}", p, base);
+        }
+    }
+    assert!(!is_strong_pseudoprime(1, 2));
+    assert!(!is_strong_pseudoprime(9, 2));
+}
+
+#[test]
+fn prime_powers() {
+    assert_eq!(is_prime_power(8), Some((2, 3)));
+    assert_eq!(is_prime_power(12), None);
+    assert_eq!(is_prime_power(2), Some((2, 1)));
+    assert_eq!(is_prime_power(0), None);
+    assert_eq!(is_prime_power(1), None);
+    assert_eq!(is_prime_power(3u64.pow(40)), Some((3, 40)));
+    assert_eq!(is_prime_power(1 << 63), Some((2, 63)));
+    // The largest prime below 2^32, squared.
+    assert_eq!(
+        is_prime_power(4_294_967_291 * 4_294_967_291),
+        Some((4_294_967_291, 2))
+    );
+    assert_eq!(is_prime_power(36), None);
+}
+
+#[test]
+fn perfect_powers() {
+    // The smallest exponent is preferred.
+    assert_eq!(perfect_power(64), Some((8, 2)));
+    assert_eq!(perfect_power(12), None);
+    assert_eq!(perfect_power(0), None);
+    assert_eq!(perfect_power(1), None);
+    assert_eq!(perfect_power(2), None);
+    assert_eq!(perfect_power(216), Some((6, 3)));
+    assert_eq!(perfect_power(1 << 63), Some((1 << 21, 3)));
+    assert_eq!(perfect_power(u64::MAX), None);
+
+    // Prime powers with `k >= 2` are perfect powers as well.
+    for n in 2..10_000 {
+        if let Some((p, k)) = is_prime_power(n) {
+            if k >= 2 {
+                let (b, j) = perfect_power(n).unwrap();
+                assert_eq!((b.pow(j), p.pow(k)), (n, n));
+                assert!(j <= k);
+            } else {
+                assert_eq!(perfect_power(n), None);
+            }
+        }
+    }
+}
+
+#[test]
+fn next_and_prev_prime() {
+    assert_eq!(next_prime(10), 11);
+    assert_eq!(next_prime(0), 2);
+    assert_eq!(next_prime(2), 3);
+    assert_eq!(next_prime(u32::MAX as u64 - 1), 4_294_967_311);
+    assert_eq!(prev_prime(3), Some(2));
+    assert_eq!(prev_prime(2), None);
+    assert_eq!(prev_prime(0), None);
+    assert_eq!(prev_prime(4_294_967_311), Some(4_294_967_291));
+
+    // Around the end of the small prime table, 104729 being the 10000th prime.
+    assert_eq!(next_prime(104_728), 104_729);
+    assert_eq!(next_prime(104_729), 104_743);
+    assert_eq!(prev_prime(104_729), Some(104_723));
+    assert_eq!(prev_prime(104_730), Some(104_729));
+    assert_eq!(prev_prime(104_744), Some(104_743));
+}
+
+#[test]
+fn is_prime_values() {
+    assert!(!is_prime(0));
+    assert!(!is_prime(1));
+    assert!(is_prime(2));
+    assert!(is_prime(1_000_003));
+    assert!(!is_prime(1_000_004));
+    assert!(!is_prime(1_000_001));
+
+    // Around where the method changes: the small table at 256, the table of
+    // the first 10^4 primes at 104729, and trial division up to 10^6.
+    for &center in &[256, 104_729, 1_000_000] {
+        for n in center - 250..center + 250 {
+            assert_eq!(is_prime(n), is_prime_u64(n as u64), "{}", n);
+        }
+    }
+}
+
+#[test]
+fn mersenne_exponents() {
+    assert_eq!(mersenne_prime_exponents(20), [2, 3, 5, 7, 13, 17, 19]);
+    // Past 64 bits, where the residues are kept in limbs.
+    assert_eq!(
+        mersenne_prime_exponents(1_300),
+        [2, 3, 5, 7, 13, 17, 19, 31, 61, 89, 107, 127, 521, 607, 1_279]
+    );
+    assert!(mersenne_prime_exponents(1).is_empty());
+}
+
+#[test]
+fn lucas_lehmer_small() {
+    assert!(lucas_lehmer(2));
+    assert!(lucas_lehmer(3));
+    assert!(!lucas_lehmer(11));
+    // The word-sized path against direct tests of `2^p - 1`.
+    for p in 2..64 {
+        assert_eq!(lucas_lehmer(p), is_prime_u64((1 << p) - 1), "p = {}", p);
+    }
+    // Just past a single word.
+    assert!(!lucas_lehmer(67));
+    assert!(lucas_lehmer(89));
+}
+
+#[test]
+fn fibonacci_prime_values() {
+    // 1597 is the next Fibonacci prime, beyond the limit of 1000.
+    assert_eq!(fibonacci_primes(1_000), [2, 3, 5, 13, 89, 233]);
+    assert_eq!(fibonacci_primes(1_597), [2, 3, 5, 13, 89, 233, 1_597]);
+    assert!(fibonacci_primes(1).is_empty());
+    // F(83) is the largest Fibonacci prime fitting in u64, and the 12th overall.
+    let primes = fibonacci_primes(u64::MAX);
+    assert_eq!(primes.last(), Some(&99_194_853_094_755_497));
+    assert_eq!(primes.len(), 12);
+
+    assert!(is_fibonacci_prime(1_597));
+    assert!(!is_fibonacci_prime(1_598));
+    // Prime, but not a Fibonacci number.
+    assert!(!is_fibonacci_prime(7));
+    // A Fibonacci number, but not prime.
+    assert!(!is_fibonacci_prime(144));
+}
+
+#[test]
+fn prime_sequences() {
+    // Mersenne numbers `2^k - 1`: prime for `k` = 2, 3, 5, 7, 13, 17, 19, 31, 61 below 64.
+    let mersenne = is_prime_sequence(1, 2, -1, 70);
+    // `2^64 - 1` and beyond would need `2^64`, so the sequence stops after 64 terms.
+    assert_eq!(mersenne.len(), 64);
+    let exponents: Vec<_> = (0..64).filter(|&k| mersenne[k]).collect();
+    assert_eq!(exponents, [2, 3, 5, 7, 13, 17, 19, 31, 61]);
+
+    // `2^k + 1` is prime for `k` = 1, 2, 4, 8, 16 below 64, the Fermat primes and 2.
+    let fermat = is_prime_sequence(1, 2, 1, 64);
+    let exponents: Vec<_> = (0..64).filter(|&k| fermat[k]).collect();
+    assert_eq!(exponents, [0, 1, 2, 4, 8, 16]);
+
+    // Negative terms are not prime.
+    // `3^k - 4` is -3, -1, 5, 23.
+    assert_eq!(is_prime_sequence(1, 3, -4, 4), [false, false, true, true]);
+    assert_eq!(is_prime_sequence(7, 1, 0, 3), [true, true, true]);
+    assert!(is_prime_sequence(2, 2, 1, 0).is_empty());
+}
@@ -0,0 +1,96 @@
+use rusty_primes::sieve::{OddSegmentedEratosthenes, SegmentedEratosthenes, Sieve};
+
+/// `π(10^k)` for `k = 1..=9`.
+const PI_POWERS_OF_TEN: [usize; 9] = [
+    4, 25, 168, 1_229, 9_592, 78_498, 664_579, 5_761_455, 50_847_534,
+];
+
+/// Returns `π(k)` for all `k <= n`, by a plain sieve independent of the crate.
+fn reference_pi(n: usize) -> Vec<usize> {
+    let mut is_prime = vec![true; n + 1];
+    is_prime[0] = false;
+    if n >= 1 {
+        is_prime[1] = false;
+    }
+    let mut i = 2;
+    while i * i <= n {
+        if is_prime[i] {
+            for j in (i * i..=n).step_by(i) {
+                is_prime[j] = false;
+            }
+        }
+        i += 1;
+    }
+    is_prime
+        .iter()
+        .scan(0, |count, &p| {
+            *count += p as usize;
+            Some(*count)
+        })
+        .collect()
+}
+
+#[test]
+fn powers_of_ten() {
+    for (k, &pi) in PI_POWERS_OF_TEN[..8].iter().enumerate() {
+        let n = 10usize.pow(k as u32 + 1);
+        assert_eq!(SegmentedEratosthenes::prime_pi(n), pi, "π(10^{})", k + 1);
+    }
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --ignored"]
+fn power_of_ten_nine() {
+    assert_eq!(
+        SegmentedEratosthenes::prime_pi(1_000_000_000),
+        PI_POWERS_OF_TEN[8]
+    );
+}
+
+#[test]
+fn small_limits() {
+    let pi = reference_pi(10_000);
+    for (n, &pi) in pi.iter().enumerate() {
+        assert_eq!(SegmentedEratosthenes::prime_pi(n), pi, "π({})", n);
+        assert_eq!(OddSegmentedEratosthenes::prime_pi(n), pi, "odd π({})", n);
+    }
+}
+
+/// Checks the limits around each place where the segment length `sqrt(n)` changes,
+/// and around the ends of the segments `(s + 1 + k s, s + (k + 1) s]` for `s = sqrt(n)`,
+/// or `[1 + 2 k s, 2 (k + 1) s - 1]` for `OddSegmentedEratosthenes`.
+#[test]
+fn segment_boundaries() {
+    const MAX: usize = 2_000_000;
+    let pi = reference_pi(MAX);
+    let mut limits = Vec::new();
+    for s in (100..=1_414).step_by(13) {
+        // `sqrt(n)` steps from `s - 1` to `s` at `n = s^2`.
+        limits.extend([s * s - 1, s * s, s * s + 1]);
+        for k in [1, 2, s / 2, s - 2] {
+            let end = s + (k + 1) * s;
+            limits.extend([end - 1, end, end + 1]);
+            let odd_end = 2 * (k + 1) * s - 1;
+            limits.extend([odd_end - 1, odd_end, odd_end + 1, odd_end + 2]);
+        }
+    }
+    for n in limits.into_iter().filter(|&n| n <= MAX) {
+        assert_eq!(SegmentedEratosthenes::prime_pi(n), pi[n], "π({})", n);
+        assert_eq!(OddSegmentedEratosthenes::prime_pi(n), pi[n], "odd π({})", n);
+    }
+}
+
+/// Checks limits drawn by a fixed-seed xorshift, so that failures are reproducible.
+#[test]
+fn random_limits() {
+    const MAX: usize = 2_000_000;
+    let pi = reference_pi(MAX);
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..500 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let n = (state % (MAX as u64 + 1)) as usize;
+        assert_eq!(SegmentedEratosthenes::prime_pi(n), pi[n], "π({})", n);
+    }
+}
@@ -0,0 +1,237 @@
+use std::{mem, panic, ptr};
+
+use rusty_primes::{
+    bitset::BitSet,
+    lehmer::LehmerPi,
+    sieve::{
+        for_each_segment, prime_pi_no_alloc, sieve_memory_estimate, sieve_no_alloc, Eratosthenes,
+        InsufficientBasePrimes, OddSegmentedEratosthenes, ParallelSegmentedEratosthenes,
+        SegmentedEratosthenes, SegmentedSieveWorkspace, Sieve,
+    },
+};
+
+/// The only test of this binary using the global table cache, which is
+/// shared by all tests of a binary and valid for a single limit.
+#[test]
+fn cached_table() {
+    let n = 100_000;
+    let table = Eratosthenes::get_or_gen_table(n);
+    assert!(ptr::eq(table, Eratosthenes::get_or_gen_table(n)));
+    assert_eq!(table.count_ones(), Eratosthenes::prime_pi(n));
+
+    let other = panic::catch_unwind(|| Eratosthenes::get_or_gen_table(n + 1));
+    assert!(other.is_err());
+}
+
+#[test]
+#[should_panic(expected = "limit too large")]
+fn prime_pi_at_usize_max() {
+    Eratosthenes::prime_pi(usize::MAX);
+}
+
+#[test]
+#[should_panic(expected = "limit too large")]
+fn gen_table_at_usize_max() {
+    Eratosthenes::gen_table(usize::MAX);
+}
+
+#[test]
+fn sieve_without_allocation() {
+    const BITS: usize = usize::BITS as usize;
+    for n in (0..=1_000).chain([BITS - 1, BITS, BITS + 1]) {
+        // A larger buffer than needed, with garbage to be cleared.
+        let mut buffer = [usize::MAX; 1_000 / BITS + 2];
+        sieve_no_alloc(&mut buffer, n);
+        let primes: Vec<_> = (0..buffer.len() * BITS)
+            .filter(|&i| buffer[i / BITS] >> (i % BITS) & 1 != 0)
+            .collect();
+        let expected: Vec<_> = Eratosthenes::gen_table(n).iter_ones().collect();
+        assert_eq!(primes, expected, "n = {}", n);
+
+        assert_eq!(prime_pi_no_alloc(&mut buffer, n), Eratosthenes::prime_pi(n));
+    }
+}
+
+#[test]
+#[should_panic(expected = "buffer too small")]
+fn sieve_without_allocation_small_buffer() {
+    let mut buffer = [0; 1_000 / usize::BITS as usize];
+    sieve_no_alloc(&mut buffer, 1_000);
+}
+
+#[test]
+fn table_and_count() {
+    const BITS: usize = usize::BITS as usize;
+    for n in (0..=1_000).chain([BITS * 100 - 1, BITS * 100, 1_000_000]) {
+        let (table, count) = Eratosthenes::gen_table_and_count(n);
+        let expected = Eratosthenes::gen_table(n);
+        assert_eq!(count, expected.count_ones(), "n = {}", n);
+        assert_eq!(table.count_ones(), count);
+        assert_eq!(table.to_bytes(), expected.to_bytes());
+    }
+}
+
+#[test]
+fn prime_pi_checked() {
+    // The table up to `usize::MAX` is not addressable.
+    assert_eq!(Eratosthenes::prime_pi_checked(usize::MAX), None);
+    // A table of 2^60 bytes exceeds any virtual address space, so the allocator refuses it.
+    assert_eq!(Eratosthenes::prime_pi_checked(usize::MAX / 2), None);
+    assert_eq!(Eratosthenes::prime_pi_checked(1_000_000), Some(78_498));
+    assert_eq!(
+        SegmentedEratosthenes::prime_pi_checked(1_000_000),
+        Some(78_498)
+    );
+    assert_eq!(Eratosthenes::prime_pi_checked(0), Some(0));
+}
+
+#[test]
+fn full_table_matches_gen_table() {
+    let limits = (0..200)
+        .chain([1_000, 4_095, 4_096, 4_097, 65_535, 65_536, 65_537])
+        .chain([1_000_000, 1_000_003]);
+    for n in limits {
+        let table = SegmentedEratosthenes::gen_full_table(n);
+        let expected = Eratosthenes::gen_table(n);
+        assert_eq!(table.len(), expected.len(), "n = {}", n);
+        assert_eq!(table.to_bytes(), expected.to_bytes(), "n = {}", n);
+        // `count_ones` reads whole words, so it also sees stray bits past the end.
+        assert_eq!(table.count_ones(), expected.count_ones(), "n = {}", n);
+    }
+}
+
+#[test]
+fn workspace_reuse() {
+    let mut workspace = SegmentedSieveWorkspace::new();
+    // The limits go up, down and back up, so the buffers are both grown and reused.
+    for &n in &[10, 1_000_000, 100, 10_000_000, 0, 1_000_000, 2, 10_000_001] {
+        assert_eq!(
+            workspace.prime_pi(n),
+            SegmentedEratosthenes::prime_pi(n),
+            "π({})",
+            n
+        );
+    }
+}
+
+#[test]
+fn prime_pi_with_base() {
+    let n = 1_000_000;
+    let base = |limit| -> Vec<u32> {
+        Eratosthenes::gen_table(limit)
+            .iter_ones()
+            .map(|p| p as u32)
+            .collect()
+    };
+    let pi = SegmentedEratosthenes::prime_pi(n);
+
+    // 997 is the largest prime up to `sqrt(n) = 1000`.
+    assert_eq!(
+        SegmentedEratosthenes::prime_pi_with_base(n, &base(997)),
+        Ok(pi)
+    );
+    // Primes past the square root are ignored.
+    assert_eq!(
+        SegmentedEratosthenes::prime_pi_with_base(n, &base(5_000)),
+        Ok(pi)
+    );
+
+    let short = base(996);
+    assert_eq!(short.last(), Some(&991));
+    let err = SegmentedEratosthenes::prime_pi_with_base(n, &short).unwrap_err();
+    assert_eq!(
+        err,
+        InsufficientBasePrimes {
+            required: 1_000,
+            largest: Some(991),
+        }
+    );
+    assert_eq!(err.to_string(), "base primes up to 991 do not cover 1000");
+
+    let err = SegmentedEratosthenes::prime_pi_with_base(n, &[]).unwrap_err();
+    assert_eq!(
+        err,
+        InsufficientBasePrimes {
+            required: 1_000,
+            largest: None,
+        }
+    );
+    assert_eq!(err.to_string(), "no base primes to cover 1000");
+
+    // No base prime is needed while `sqrt(n) < 2`.
+    assert_eq!(SegmentedEratosthenes::prime_pi_with_base(1, &[]), Ok(0));
+    assert_eq!(SegmentedEratosthenes::prime_pi_with_base(3, &[]), Ok(2));
+    assert!(SegmentedEratosthenes::prime_pi_with_base(4, &[]).is_err());
+}
+
+#[test]
+fn prime_pi_with_stats() {
+    let n = 1_000_000;
+    let (pi, stats) = SegmentedEratosthenes::prime_pi_with_stats(n);
+    assert_eq!(pi, SegmentedEratosthenes::prime_pi(n));
+    assert_eq!(stats.seg_len, 1_000);
+    // The segments cover `(1000, 10^6]`, after the 168 base primes.
+    assert_eq!(stats.segments(), 999);
+    assert_eq!(stats.time_per_segment.len(), 999);
+    assert_eq!(stats.primes_per_segment.iter().sum::<usize>(), pi - 168);
+    assert!(stats.marks > 0);
+
+    let text = stats.to_string();
+    assert!(text.starts_with("segments: 999 of length 1000\nmarks: "));
+    assert!(text.contains("\nprimes per segment: min "));
+    assert!(text.contains("\ntime per segment: min "));
+
+    let (pi, stats) = SegmentedEratosthenes::prime_pi_with_stats(1);
+    assert_eq!(pi, 0);
+    assert_eq!(stats.segments(), 0);
+    assert_eq!(stats.to_string(), "segments: 0 of length 0\nmarks: 0\n");
+}
+
+#[test]
+fn segments_cover_the_range() {
+    for &n in &[0, 1, 2, 3, 100, 99_999, 100_000, 1_000_000, 1_000_003] {
+        let mut count = 0;
+        let mut next_low = 0;
+        for_each_segment(n, |seg, low| {
+            // The segments are contiguous, starting from 0.
+            assert_eq!(low, next_low, "n = {}", n);
+            assert!(low <= n, "n = {}", n);
+            next_low = low + seg.len();
+            count += seg.count_ones();
+        });
+        assert_eq!(count, SegmentedEratosthenes::prime_pi(n), "π({})", n);
+        if n >= 2 {
+            assert!(next_low > n, "n = {}", n);
+        }
+    }
+}
+
+#[test]
+fn verify_all_sieves() {
+    assert!(Eratosthenes::verify());
+    assert!(SegmentedEratosthenes::verify());
+    assert!(OddSegmentedEratosthenes::verify());
+    assert!(ParallelSegmentedEratosthenes::verify());
+    assert!(LehmerPi::verify());
+}
+
+#[test]
+fn table_memory() {
+    const WORD: usize = mem::size_of::<usize>();
+    let header = mem::size_of::<BitSet>();
+    assert_eq!(BitSet::new(1, true).memory_bytes(), header + WORD);
+    assert_eq!(BitSet::new(64, true).memory_bytes(), header + 64 / 8);
+    // 1000 bits are rounded up to 1024.
+    assert_eq!(BitSet::new(1_000, false).memory_bytes(), header + 128);
+
+    // The estimate matches the table it stands for, without generating it.
+    for n in [0, 1, 62, 63, 64, 127, 128, 1_000, 1_000_000] {
+        assert_eq!(
+            sieve_memory_estimate(n),
+            Eratosthenes::gen_table(n).memory_bytes(),
+            "n = {}",
+            n
+        );
+    }
+    assert_eq!(sieve_memory_estimate(999_999), header + 125_000);
+}
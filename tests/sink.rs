@@ -0,0 +1,98 @@
+use std::convert::TryInto;
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+use std::io::Read;
+
+use rusty_primes::{
+    sieve::{Eratosthenes, SegmentedEratosthenes},
+    sink::{RecordWidth, WriteSink},
+};
+
+const LIMIT: usize = 1_000_000;
+
+/// Returns the raw records of the primes up to `LIMIT`.
+fn raw_records(width: RecordWidth) -> Vec<u8> {
+    let mut sink = WriteSink::new(Vec::new(), width);
+    SegmentedEratosthenes::stream_primes(LIMIT, &mut sink);
+    sink.into_result().unwrap()
+}
+
+#[test]
+fn raw_round_trip() {
+    let primes: Vec<u64> = Eratosthenes::gen_table(LIMIT)
+        .iter_ones()
+        .map(|p| p as u64)
+        .collect();
+
+    let records = raw_records(RecordWidth::U32);
+    assert_eq!(records.len(), primes.len() * 4);
+    let decoded: Vec<u64> = records
+        .chunks_exact(4)
+        .map(|r| u32::from_be_bytes(r.try_into().unwrap()) as u64)
+        .collect();
+    assert_eq!(decoded, primes);
+
+    let records = raw_records(RecordWidth::U64);
+    assert_eq!(records.len(), primes.len() * 8);
+    let decoded: Vec<u64> = records
+        .chunks_exact(8)
+        .map(|r| u64::from_be_bytes(r.try_into().unwrap()))
+        .collect();
+    assert_eq!(decoded, primes);
+}
+
+#[test]
+fn vec_sink() {
+    for n in 0..300 {
+        let mut primes = Vec::new();
+        SegmentedEratosthenes::stream_primes(n, &mut primes);
+        let expected: Vec<u64> = Eratosthenes::gen_table(n)
+            .iter_ones()
+            .map(|p| p as u64)
+            .collect();
+        assert_eq!(primes, expected, "n = {}", n);
+    }
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn gzip_round_trip() {
+    use flate2::read::GzDecoder;
+    use rusty_primes::sink::GzipPrimeSink;
+
+    for &width in &[RecordWidth::U32, RecordWidth::U64] {
+        let mut sink = GzipPrimeSink::new(Vec::new(), width, 6);
+        SegmentedEratosthenes::stream_primes(LIMIT, &mut sink);
+        assert!(sink.error().is_none());
+        let compressed = sink.into_result().unwrap();
+
+        let raw = raw_records(width);
+        assert!(compressed.len() < raw.len());
+        let mut decompressed = Vec::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, raw);
+    }
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_round_trip() {
+    use rusty_primes::sink::ZstdPrimeSink;
+
+    for &width in &[RecordWidth::U32, RecordWidth::U64] {
+        let mut sink = ZstdPrimeSink::new(Vec::new(), width, 0).unwrap();
+        SegmentedEratosthenes::stream_primes(LIMIT, &mut sink);
+        assert!(sink.error().is_none());
+        let compressed = sink.into_result().unwrap();
+
+        let raw = raw_records(width);
+        assert!(compressed.len() < raw.len());
+        let mut decompressed = Vec::new();
+        zstd::Decoder::new(&compressed[..])
+            .unwrap()
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, raw);
+    }
+}
@@ -0,0 +1,41 @@
+use rusty_primes::{
+    sieve::Eratosthenes,
+    stack::{words_for, StackSieve},
+};
+
+/// Checks a `StackSieve` of `WORDS` words against `gen_table` for `limit`,
+/// and again after re-sieving it for every smaller limit.
+fn check<const WORDS: usize>(limit: usize) {
+    let mut sieve = StackSieve::<WORDS>::new(limit);
+    for n in (0..=limit).rev() {
+        if n != limit {
+            sieve.sieve(n);
+        }
+        let table = Eratosthenes::gen_table(n);
+        assert_eq!(sieve.limit(), n);
+        assert_eq!(sieve.count_ones(), table.count_ones(), "π({})", n);
+        assert_eq!(
+            sieve.primes().collect::<Vec<_>>(),
+            table.iter_ones().collect::<Vec<_>>(),
+            "n = {}",
+            n
+        );
+        for i in 0..=n {
+            assert_eq!(sieve.is_prime(i), unsafe { table.get(i) }, "{}", i);
+        }
+    }
+}
+
+#[test]
+fn matches_gen_table() {
+    check::<{ words_for(63) }>(63);
+    check::<{ words_for(64) }>(64);
+    check::<{ words_for(65) }>(65);
+    check::<{ words_for(1000) }>(1000);
+}
+
+#[test]
+#[should_panic(expected = "buffer too small")]
+fn too_few_words() {
+    let _ = StackSieve::<{ words_for(63) }>::new(64);
+}
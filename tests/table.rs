@@ -0,0 +1,59 @@
+use rusty_primes::table::{PrimeList, PrimeSieve};
+
+#[test]
+fn primes_congruent_to() {
+    let sieve = PrimeSieve::new(100);
+    let primes: Vec<_> = sieve.primes_congruent_to(3, 4).take(5).collect();
+    assert_eq!(primes, [3, 7, 11, 19, 23]);
+    let primes: Vec<_> = sieve.primes_congruent_to(5, 4).take(5).collect();
+    assert_eq!(primes, [5, 13, 17, 29, 37]);
+    assert_eq!(sieve.primes_congruent_to(0, 1).count(), 25);
+}
+
+#[test]
+#[should_panic(expected = "zero modulus")]
+fn primes_congruent_to_zero_modulus() {
+    let _ = PrimeSieve::new(100).primes_congruent_to(3, 0);
+}
+
+#[test]
+fn contains_all_and_filter_primes() {
+    let sieve = PrimeSieve::new(100);
+    assert!(sieve.contains_all(&[2, 3, 97]));
+    assert!(!sieve.contains_all(&[2, 4]));
+    assert!(sieve.contains_all(&[]));
+    assert_eq!(sieve.filter_primes(&[10, 7, 2, 9, 97, 1, 0]), [7, 2, 97]);
+
+    // Values above the limit are treated as not prime, even primes such as 101.
+    assert!(!sieve.contains_all(&[100]));
+    assert!(!sieve.contains_all(&[2, 101]));
+    assert!(!sieve.contains_all(&[usize::MAX]));
+    assert_eq!(sieve.filter_primes(&[101, 3, 100, usize::MAX, 5]), [3, 5]);
+}
+
+#[test]
+fn prime_list() {
+    let list = PrimeList::new(100);
+    assert_eq!(list.limit(), 100);
+    assert_eq!(list.as_slice().len(), 25);
+    assert_eq!(list.as_slice()[..5], [2, 3, 5, 7, 11]);
+    assert!(list.contains(2));
+    assert!(list.contains(97));
+    assert!(!list.contains(1));
+    assert!(!list.contains(91));
+    // Beyond the limit, even primes are not in the list.
+    assert!(!list.contains(101));
+    assert_eq!(list.binary_search(97), Ok(24));
+    assert_eq!(list.binary_search(4), Err(2));
+    assert_eq!(list.binary_search(1_000), Err(25));
+    assert!(PrimeList::new(1).as_slice().is_empty());
+
+    let sieve = PrimeSieve::new(1_000_000);
+    let list = PrimeList::new(1_000_000);
+    assert_eq!(sieve.to_prime_list(), list);
+    assert!(list
+        .as_slice()
+        .iter()
+        .map(|&p| p as usize)
+        .eq(sieve.primes()));
+}